@@ -0,0 +1,221 @@
+//! A compact binary encoding of the tokens of a source, so that the tokens of
+//! unchanged files can be cached instead of lexing them again.
+//!
+//! The encoding starts with a version byte, followed by the number of errors
+//! that the lexer reported as a LEB128 varint. Every error is encoded as a
+//! byte for its kind, and the start and length of its span as varints. Every
+//! token is then encoded as a byte for its kind, a byte for the variant
+//! within that kind, and the distance of its start from the end of the
+//! previous token as well as its length, both as varints.
+
+use crate::lexer::error::Error;
+use crate::lexer::span::{Span, Spanned};
+use crate::lexer::token::{Comment, Ident, Keyword, Literal, Operator, Separator, Token};
+use crate::lexer::Lexer;
+
+/// Changes whenever the encoding changes, so that stale caches are rejected.
+const FORMAT_VERSION: u8 = 3;
+
+const KIND_KEYWORD: u8 = 0;
+const KIND_IDENT: u8 = 1;
+const KIND_LITERAL: u8 = 2;
+const KIND_OPERATOR: u8 = 3;
+const KIND_SEPARATOR: u8 = 4;
+const KIND_COMMENT: u8 = 5;
+const KIND_UNKNOWN: u8 = 6;
+
+const ERROR_ILLEGAL_ESCAPE_SEQUENCE: u8 = 0;
+const ERROR_UNTERMINATED_STRING: u8 = 1;
+const ERROR_UNTERMINATED_COMMENT: u8 = 2;
+const ERROR_MALFORMED_INTEGER_LITERAL: u8 = 3;
+
+impl Lexer<'_> {
+    /// Encodes all tokens of the source and the errors that were reported
+    /// while lexing them in a compact binary format, which can be decoded
+    /// with [`Lexer::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut iter = self.tokens();
+        let tokens = iter.by_ref().collect::<Vec<_>>();
+
+        let mut bytes = vec![FORMAT_VERSION];
+        write_varint(&mut bytes, iter.errors().len());
+        for error in iter.errors() {
+            let kind = match error {
+                Error::IllegalEscapeSequence { .. } => ERROR_ILLEGAL_ESCAPE_SEQUENCE,
+                Error::UnterminatedString { .. } => ERROR_UNTERMINATED_STRING,
+                Error::UnterminatedComment { .. } => ERROR_UNTERMINATED_COMMENT,
+                Error::MalformedIntegerLiteral { .. } => ERROR_MALFORMED_INTEGER_LITERAL,
+            };
+            bytes.push(kind);
+            let span = error.span().expect("lexer errors always have a span");
+            let start = usize::from(span.start());
+            write_varint(&mut bytes, start);
+            write_varint(&mut bytes, usize::from(span.end()) - start);
+        }
+
+        let mut previous_end = 0;
+        for token in tokens {
+            let (kind, variant) = match token {
+                Token::Keyword(keyword) => (KIND_KEYWORD, keyword.index()),
+                Token::Ident(_) => (KIND_IDENT, 0),
+                Token::Literal(literal) => (KIND_LITERAL, literal.index()),
+                Token::Operator(operator) => (KIND_OPERATOR, operator.index()),
+                Token::Separator(separator) => (KIND_SEPARATOR, separator.index()),
+                Token::Comment(comment) => (KIND_COMMENT, comment.index()),
+                Token::Unknown(_) => (KIND_UNKNOWN, 0),
+            };
+            bytes.push(kind);
+            bytes.push(variant);
+
+            let start = usize::from(token.span().start());
+            let end = usize::from(token.span().end());
+            write_varint(&mut bytes, start - previous_end);
+            write_varint(&mut bytes, end - start);
+            previous_end = end;
+        }
+        bytes
+    }
+
+    /// Decodes the tokens and errors that were encoded with
+    /// [`Lexer::to_bytes`]. Returns `None` if the bytes are malformed or were
+    /// written by a different version of the encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Option<(Vec<Token>, Vec<Error>)> {
+        let (&version, mut bytes) = bytes.split_first()?;
+        if version != FORMAT_VERSION {
+            return None;
+        }
+
+        let mut errors = vec![];
+        for _ in 0..read_varint(&mut bytes)? {
+            let (&kind, rest) = bytes.split_first()?;
+            bytes = rest;
+            let start = read_varint(&mut bytes)?;
+            let span = Span::new(start, start.checked_add(read_varint(&mut bytes)?)?);
+            errors.push(match kind {
+                ERROR_ILLEGAL_ESCAPE_SEQUENCE => Error::IllegalEscapeSequence { span },
+                ERROR_UNTERMINATED_STRING => Error::UnterminatedString { span },
+                ERROR_UNTERMINATED_COMMENT => Error::UnterminatedComment { span },
+                ERROR_MALFORMED_INTEGER_LITERAL => Error::MalformedIntegerLiteral { span },
+                _ => return None,
+            });
+        }
+
+        let mut tokens = vec![];
+        let mut previous_end = 0_usize;
+        while let [kind, variant, rest @ ..] = bytes {
+            bytes = rest;
+            let start = previous_end.checked_add(read_varint(&mut bytes)?)?;
+            let end = start.checked_add(read_varint(&mut bytes)?)?;
+            let span = Span::new(start, end);
+            tokens.push(match *kind {
+                KIND_KEYWORD => Token::Keyword(Keyword::from_index(*variant, span)?),
+                KIND_IDENT => Token::Ident(Ident::new(span)),
+                KIND_LITERAL => Token::Literal(Literal::from_index(*variant, span)?),
+                KIND_OPERATOR => Token::Operator(Operator::from_index(*variant, span)?),
+                KIND_SEPARATOR => Token::Separator(Separator::from_index(*variant, span)?),
+                KIND_COMMENT => Token::Comment(Comment::from_index(*variant, span)?),
+                KIND_UNKNOWN => Token::Unknown(span),
+                _ => return None,
+            });
+            previous_end = end;
+        }
+        // a single trailing byte is a truncated token
+        bytes.is_empty().then_some((tokens, errors))
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Option<usize> {
+    let mut value = 0_usize;
+    let mut shift = 0;
+    loop {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        value |= usize::from(byte & 0x7F).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let input = r#"
+package foo;
+
+/** Docs */
+public class A {
+    // a comment
+    int[] a = { 1, 0x2F, 'c', 1.5 };
+    String s = "abc" + null + true;
+    #
+}
+"#;
+        let lexer = Lexer::from(input);
+        let bytes = lexer.to_bytes();
+        let (tokens, errors) = Lexer::from_bytes(&bytes).expect("bytes must decode");
+        assert_eq!(tokens, lexer.tokens().collect::<Vec<_>>());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_errors() {
+        let lexer = Lexer::from("class A { String s = \"a\\q\"; } /* unterminated");
+        let mut iter = lexer.tokens();
+        let expected_tokens = iter.by_ref().collect::<Vec<_>>();
+        assert_eq!(iter.errors().len(), 2, "{:?}", iter.errors());
+
+        let (tokens, errors) = Lexer::from_bytes(&lexer.to_bytes()).expect("bytes must decode");
+        assert_eq!(tokens, expected_tokens);
+        assert_eq!(errors, iter.errors());
+        assert!(matches!(
+            errors.last(),
+            Some(Error::UnterminatedComment { .. })
+        ));
+    }
+
+    #[test]
+    fn test_malformed_bytes() {
+        assert_eq!(Lexer::from_bytes(&[]), None);
+        assert_eq!(Lexer::from_bytes(&[FORMAT_VERSION + 1]), None);
+        assert_eq!(Lexer::from_bytes(&[FORMAT_VERSION]), None);
+        assert_eq!(
+            Lexer::from_bytes(&[FORMAT_VERSION, 0]),
+            Some((vec![], vec![]))
+        );
+        // a token without its span
+        assert_eq!(Lexer::from_bytes(&[FORMAT_VERSION, 0, KIND_IDENT, 0]), None);
+        // an unknown keyword
+        assert_eq!(
+            Lexer::from_bytes(&[FORMAT_VERSION, 0, KIND_KEYWORD, 200, 0, 1]),
+            None
+        );
+        // an error that is missing
+        assert_eq!(Lexer::from_bytes(&[FORMAT_VERSION, 1]), None);
+        // an unknown error
+        assert_eq!(Lexer::from_bytes(&[FORMAT_VERSION, 1, 200, 0, 1]), None);
+    }
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 0x7F, 0x80, 300, usize::MAX] {
+            let mut bytes = vec![];
+            write_varint(&mut bytes, value);
+            let mut slice = bytes.as_slice();
+            assert_eq!(read_varint(&mut slice), Some(value));
+            assert!(slice.is_empty());
+        }
+    }
+}
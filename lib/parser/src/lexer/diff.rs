@@ -0,0 +1,94 @@
+use core::ops::Range;
+
+use crate::lexer::token::Token;
+
+/// The tokens that changed after an edit of the source.
+///
+/// Tokens are compared by their kind and their span, which is what matters
+/// for highlighting. Tokens after the edit are considered unchanged if they
+/// are only shifted by the length difference of the edit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TokenDiff {
+    old: Range<usize>,
+    new: Range<usize>,
+}
+
+impl TokenDiff {
+    pub(in crate::lexer) fn between(old: &[Token], new: &[Token]) -> Self {
+        let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+
+        let (old_rest, new_rest) = (&old[prefix..], &new[prefix..]);
+        // the distance by which the tokens after the edit were moved
+        let shift = match (old_rest.last(), new_rest.last()) {
+            (Some(a), Some(b)) => offset(b) - offset(a),
+            _ => 0,
+        };
+        let suffix = old_rest
+            .iter()
+            .rev()
+            .zip(new_rest.iter().rev())
+            .take_while(|(a, b)| {
+                a.same_kind(b) && offset(b) - offset(a) == shift && span_len(a) == span_len(b)
+            })
+            .count();
+
+        Self {
+            old: prefix..old.len() - suffix,
+            new: prefix..new.len() - suffix,
+        }
+    }
+
+    /// The indices of the tokens in the old token list that were removed or changed.
+    pub fn old_range(&self) -> Range<usize> {
+        self.old.clone()
+    }
+
+    /// The indices of the tokens in the new token list that were inserted or changed.
+    pub fn new_range(&self) -> Range<usize> {
+        self.new.clone()
+    }
+
+    /// Returns whether no token changed.
+    pub fn is_empty(&self) -> bool {
+        self.old.is_empty() && self.new.is_empty()
+    }
+}
+
+fn offset(token: &Token) -> isize {
+    usize::from(token.span().start()) as isize
+}
+
+fn span_len(token: &Token) -> usize {
+    usize::from(token.span().end()) - usize::from(token.span().start())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    fn diff(old: &str, new: &str) -> TokenDiff {
+        let old = Lexer::from(old).tokens().collect::<Vec<_>>();
+        Lexer::token_diff(&old, Lexer::from(new).source())
+    }
+
+    #[test]
+    fn test_inserted_token() {
+        let diff = diff("f(a, c);", "f(a, 1, c);");
+        assert_eq!(diff.old_range(), 4..4);
+        assert_eq!(diff.new_range(), 4..6);
+    }
+
+    #[test]
+    fn test_changed_token() {
+        let diff = diff("int x = 1;", "int x = y;");
+        assert_eq!(diff.old_range(), 3..4);
+        assert_eq!(diff.new_range(), 3..4);
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let diff = diff("int x = 1;", "int x = 1;");
+        assert!(diff.is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use crate::lexer::span::{Span, Spanned};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    #[error("illegal escape sequence in string literal")]
+    IllegalEscapeSequence { span: Span },
+    #[error("unterminated string literal")]
+    UnterminatedString { span: Span },
+    #[error("unterminated block comment")]
+    UnterminatedComment { span: Span },
+    #[error("integer literal has no digits after its radix prefix")]
+    MalformedIntegerLiteral { span: Span },
+}
+
+impl Error {
+    /// A stable code that identifies the kind of this error, see
+    /// [`crate::parser::error::Error::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::IllegalEscapeSequence { .. } => "E0101",
+            Error::UnterminatedString { .. } => "E0102",
+            Error::UnterminatedComment { .. } => "E0103",
+            Error::MalformedIntegerLiteral { .. } => "E0104",
+        }
+    }
+}
+
+impl Spanned for Error {
+    fn span(&self) -> Option<Span> {
+        match self {
+            Error::IllegalEscapeSequence { span }
+            | Error::UnterminatedString { span }
+            | Error::UnterminatedComment { span }
+            | Error::MalformedIntegerLiteral { span } => Some(*span),
+        }
+    }
+}
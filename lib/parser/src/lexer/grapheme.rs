@@ -9,6 +9,20 @@ impl core::fmt::Debug for GraphemeIndex {
     }
 }
 
+impl GraphemeIndex {
+    /// Subtracts `rhs` from this index, returning `None` instead of
+    /// underflowing.
+    pub fn checked_sub(self, rhs: usize) -> Option<GraphemeIndex> {
+        self.0.checked_sub(rhs).map(GraphemeIndex)
+    }
+
+    /// Subtracts `rhs` from this index, clamping the result to zero instead
+    /// of underflowing.
+    pub fn saturating_sub(self, rhs: usize) -> GraphemeIndex {
+        GraphemeIndex(self.0.saturating_sub(rhs))
+    }
+}
+
 impl From<usize> for GraphemeIndex {
     fn from(index: usize) -> Self {
         Self(index)
@@ -64,3 +78,28 @@ impl AddAssign<usize> for GraphemeIndex {
         self.0 += rhs;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_sub() {
+        assert_eq!(GraphemeIndex::from(0).checked_sub(1), None);
+        assert_eq!(
+            GraphemeIndex::from(3).checked_sub(1),
+            Some(GraphemeIndex::from(2))
+        );
+        assert_eq!(
+            GraphemeIndex::from(3).checked_sub(3),
+            Some(GraphemeIndex::from(0))
+        );
+    }
+
+    #[test]
+    fn test_saturating_sub() {
+        assert_eq!(GraphemeIndex::from(0).saturating_sub(1), 0.into());
+        assert_eq!(GraphemeIndex::from(2).saturating_sub(5), 0.into());
+        assert_eq!(GraphemeIndex::from(5).saturating_sub(2), 3.into());
+    }
+}
@@ -1,14 +1,22 @@
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::lexer::error::Error;
 use crate::lexer::source::Source;
 use crate::lexer::span::Span;
+pub use diff::*;
 pub use grapheme::*;
+pub use string_groups::*;
 
-use crate::lexer::token::{Ident, Keyword, Literal, Operator, Separator, Token};
+use crate::lexer::token::{Comment, Ident, Keyword, Literal, Operator, Separator, Token};
 
+#[cfg(feature = "token-cache")]
+mod cache;
+mod diff;
+pub mod error;
 mod grapheme;
 pub mod source;
 pub mod span;
+mod string_groups;
 pub mod token;
 
 fn is_java_whitespace(c: char) -> bool {
@@ -27,6 +35,29 @@ fn is_java_whitespace(c: char) -> bool {
         || c == '\u{001F}'
 }
 
+/// Returns whether the given string is a Java keyword, including the reserved
+/// but unused `const` and `goto`. The check is case-sensitive, so `Class` is
+/// not a keyword.
+pub fn is_keyword(s: &str) -> bool {
+    Lexer::is_keyword(s)
+}
+
+/// Returns whether the given string is one of the literals `true`, `false`
+/// and `null`, which look like keywords but can't be used as identifiers
+/// either.
+pub fn is_reserved_literal(s: &str) -> bool {
+    Lexer::is_reserved_literal(s)
+}
+
+const BYTE_ORDER_MARK: char = '\u{FEFF}';
+
+/// The length of the longest keyword, `synchronized`.
+const MAX_KEYWORD_LENGTH: usize = 12;
+
+fn is_line_terminator(c: char) -> bool {
+    c == '\n' || c == '\r'
+}
+
 fn is_java_identifier_start(c: char) -> bool {
     c.is_alphabetic() || c == '_' || c == '$'
 }
@@ -38,17 +69,29 @@ fn is_java_identifier_part(c: char) -> bool {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Lexer<'a> {
     source: Source<'a>,
+    /// Whether unterminated string literals end at the end of their line.
+    string_recovery: bool,
 }
 
 impl<'a> From<&'a str> for Lexer<'a> {
     fn from(input: &'a str) -> Self {
         Self {
             source: Source::from(input),
+            string_recovery: false,
         }
     }
 }
 
 impl<'a> Lexer<'a> {
+    /// Makes an unterminated string literal end at the end of its line instead
+    /// of the end of the input, so that lexing resumes on the next line. This
+    /// is useful in editors, where the closing quote is often still missing
+    /// while typing. The string is still reported as unterminated.
+    pub fn with_string_recovery(mut self) -> Self {
+        self.string_recovery = true;
+        self
+    }
+
     pub fn tokens(&'a self) -> TokenIterator<'a> {
         TokenIterator::new(self)
     }
@@ -57,6 +100,36 @@ impl<'a> Lexer<'a> {
         &self.source
     }
 
+    /// Returns the source text of the given token.
+    pub fn token_text(&'a self, token: &Token) -> Option<&'a str> {
+        self.source.resolve_span(*token.span())
+    }
+
+    /// Lexes the new source and returns the range of tokens that changed,
+    /// compared to the given old tokens.
+    pub fn token_diff(old: &[Token], new_source: &Source) -> TokenDiff {
+        let lexer = Lexer {
+            source: new_source.clone(),
+            string_recovery: false,
+        };
+        let new = lexer.tokens().collect::<Vec<_>>();
+        TokenDiff::between(old, &new)
+    }
+
+    /// Returns whether the given string is a Java keyword, including the
+    /// reserved but unused `const` and `goto`. The check is case-sensitive,
+    /// so `Class` is not a keyword.
+    pub fn is_keyword(s: &str) -> bool {
+        token::KEYWORDS.contains(&s)
+    }
+
+    /// Returns whether the given string is one of the literals `true`, `false`
+    /// and `null`, which look like keywords but can't be used as identifiers
+    /// either.
+    pub fn is_reserved_literal(s: &str) -> bool {
+        token::BOOLEAN_VALUES.contains(&s) || s == token::NULL_LITERAL
+    }
+
     #[inline]
     pub fn matches(&self, offset: GraphemeIndex, s: &str) -> bool {
         self.source.matches(offset, s)
@@ -73,10 +146,13 @@ impl<'a> Lexer<'a> {
     where
         F: Fn(char) -> bool,
     {
+        // indexing instead of skipping keeps this independent of the offset,
+        // otherwise advancing over a long token would be quadratic
         self.source
             .grapheme_indices()
+            .get(offset.into()..)
+            .unwrap_or_default()
             .iter()
-            .skip(offset.into())
             .take_while(|(_, c)| f(*c))
             .count()
     }
@@ -85,16 +161,33 @@ impl<'a> Lexer<'a> {
 pub struct TokenIterator<'a> {
     lexer: &'a Lexer<'a>,
     char_index: GraphemeIndex,
+    errors: Vec<Error>,
 }
 
 impl<'a> TokenIterator<'a> {
     fn new(lexer: &'a Lexer) -> Self {
+        // a leading byte order mark is not part of the source
+        let char_index = match lexer.char_at(0.into()) {
+            Some(BYTE_ORDER_MARK) => 1,
+            _ => 0,
+        };
         Self {
             lexer,
-            char_index: 0.into(),
+            char_index: char_index.into(),
+            errors: vec![],
         }
     }
 
+    /// Groups adjacent string literals, see [`StringGroups`].
+    pub fn group_strings(self) -> StringGroups<Self> {
+        StringGroups::new(self)
+    }
+
+    /// Returns the errors that were encountered in the tokens lexed so far.
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
     fn advance_while<F>(&mut self, f: F)
     where
         F: Fn(char) -> bool,
@@ -106,29 +199,80 @@ impl<'a> TokenIterator<'a> {
         self.advance_while(is_java_whitespace);
     }
 
-    fn next_keyword(&mut self) -> Option<Keyword> {
-        for &keyword in token::KEYWORDS.iter() {
-            if self.lexer.matches(self.char_index, keyword) {
-                let start_index = self.char_index;
-                self.char_index += UnicodeSegmentation::graphemes(keyword, true).count(); // technically this could be .len() since the keywords only consist of 1byte characters
+    fn next_line_comment(&mut self) -> Option<Comment> {
+        if self.lexer.matches(self.char_index, "//") {
+            let start_index = self.char_index;
+            self.advance_while(|c| !is_line_terminator(c));
+            let span = Span::new(start_index, self.char_index);
+            return Some(Comment::new_line(span));
+        }
+        None
+    }
 
+    fn next_block_comment(&mut self) -> Option<Comment> {
+        if !self.lexer.matches(self.char_index, "/*") {
+            return None;
+        }
+
+        let start_index = self.char_index;
+        self.char_index += 2;
+        // block comments don't nest, so the first */ closes the comment
+        while !self.lexer.matches(self.char_index, "*/") {
+            if self.lexer.char_at(self.char_index).is_none() {
                 let span = Span::new(start_index, self.char_index);
-                let keyword = Keyword::try_from_str(keyword, span).unwrap(); // never fails because we just matched it
-                return Some(keyword);
+                self.errors.push(Error::UnterminatedComment { span });
+                return Some(Comment::new_block(span));
             }
+            self.char_index += 1;
         }
-        None
+        self.char_index += 2;
+        Some(Comment::new_block(Span::new(start_index, self.char_index)))
+    }
+
+    /// Lexes a whole word that could be an identifier, and classifies it as
+    /// a keyword, a boolean or null literal, or an identifier. Since the word
+    /// is read completely first, a keyword is never lexed from the prefix of
+    /// an identifier, like `class` in `classroom`.
+    fn next_word(&mut self) -> Option<Token> {
+        let first = self.lexer.char_at(self.char_index)?;
+        if !is_java_identifier_start(first) {
+            return None;
+        }
+
+        let start_index = self.char_index;
+        self.advance_while(is_java_identifier_part);
+        let span = Span::new(start_index, self.char_index);
+        // no keyword or literal is longer than this, so longer words don't
+        // need to be collected
+        let length = self.char_index.saturating_sub(start_index.into());
+        if Into::<usize>::into(length) > MAX_KEYWORD_LENGTH {
+            return Some(Token::Ident(Ident::new(span)));
+        }
+
+        let word = self.lexer.source.grapheme_indices()[start_index.into()..self.char_index.into()]
+            .iter()
+            .map(|(_, c)| *c)
+            .collect::<String>();
+        let token = match word.as_str() {
+            token::BOOLEAN_TRUE | token::BOOLEAN_FALSE => {
+                Token::Literal(Literal::new_boolean(span))
+            }
+            token::NULL_LITERAL => Token::Literal(Literal::new_null(span)),
+            word => match Keyword::try_from_str(word, span) {
+                Some(keyword) => Token::Keyword(keyword),
+                None => Token::Ident(Ident::new(span)),
+            },
+        };
+        Some(token)
     }
 
     fn next_operator(&mut self) -> Option<Operator> {
-        // TODO: support more than just arithmetic operators
-        for &operator in token::ARITHMETIC_OPERATOR_VALUES.iter() {
+        for &operator in token::OPERATORS.iter() {
             if self.lexer.matches(self.char_index, operator) {
                 let start_index = self.char_index;
                 self.char_index += UnicodeSegmentation::graphemes(operator, true).count(); // technically this could be .len() since the keywords only consist of 1byte characters
 
                 let span = Span::new(start_index, self.char_index);
-                // TODO: the comment on the line below assumes that we've implemented all operators, which is not the case yet
                 let op = Operator::try_from_str(operator, span).unwrap(); // never fails because we just matched it
                 return Some(op);
             }
@@ -149,75 +293,138 @@ impl<'a> TokenIterator<'a> {
         None
     }
 
-    fn next_identifier(&mut self) -> Option<Ident> {
-        let current_char = match self.lexer.char_at(self.char_index) {
-            Some(c) => c,
-            None => {
-                // TODO: return a proper error
-                panic!("unexpected end of input");
-            }
-        };
-        if is_java_identifier_start(current_char) {
-            let start_index = self.char_index;
-            self.advance_while(is_java_identifier_part);
-            let span = Span::new(start_index, self.char_index);
-            let identifier = Ident::new(span);
-            return Some(identifier);
-        }
-        None
-    }
-
     fn next_literal(&mut self) -> Option<Literal> {
         // is it a string?
         if let Some(string_literal) = self.next_string_literal() {
             return Some(string_literal);
         }
 
-        // is it a boolean?
-        if let Some(boolean_literal) = self.next_boolean_literal() {
-            return Some(boolean_literal);
+        // is it an integer?
+        if let Some(integer_literal) = self.next_integer_literal() {
+            return Some(integer_literal);
         }
 
         None
     }
 
-    fn next_boolean_literal(&mut self) -> Option<Literal> {
-        for &boolean_value in token::BOOLEAN_VALUES.iter() {
-            if self.lexer.matches(self.char_index, boolean_value) {
-                let start_index = self.char_index;
-                self.char_index += UnicodeSegmentation::graphemes(boolean_value, true).count(); // technically this could be .len() since the keywords only consist of 1byte characters
-                let span = Span::new(start_index, self.char_index);
-                let boolean = Literal::new_boolean(span);
-                return Some(boolean);
+    fn next_integer_literal(&mut self) -> Option<Literal> {
+        let first = self.lexer.char_at(self.char_index)?;
+        if !first.is_ascii_digit() {
+            return None;
+        }
+
+        let start_index = self.char_index;
+        let second = self.lexer.char_at(self.char_index + 1);
+        let mut has_digits = true;
+        match (first, second) {
+            ('0', Some('x' | 'X')) => {
+                self.char_index += 2;
+                has_digits = self.advance_over_digits(|c| c.is_ascii_hexdigit());
+            }
+            ('0', Some('b' | 'B')) => {
+                self.char_index += 2;
+                has_digits = self.advance_over_digits(|c| c == '0' || c == '1');
+            }
+            ('0', _) => {
+                // a leading zero denotes an octal literal, or just a zero
+                self.advance_while(|c| ('0'..='7').contains(&c) || c == '_');
             }
+            _ => self.advance_while(|c| c.is_ascii_digit() || c == '_'),
         }
-        None
+        if let Some('l' | 'L') = self.lexer.char_at(self.char_index) {
+            self.char_index += 1;
+        }
+
+        let span = Span::new(start_index, self.char_index);
+        if !has_digits {
+            self.errors.push(Error::MalformedIntegerLiteral { span });
+        }
+        Some(Literal::new_integer(span))
+    }
+
+    /// Advances over digits and underscores, and returns whether there was
+    /// at least one digit.
+    fn advance_over_digits<F>(&mut self, is_digit: F) -> bool
+    where
+        F: Fn(char) -> bool,
+    {
+        let underscores = self
+            .lexer
+            .count_consecutive_matches(self.char_index, |c| c == '_');
+        let start_index = self.char_index;
+        self.advance_while(|c| is_digit(c) || c == '_');
+        self.char_index > start_index + underscores
     }
 
     fn next_string_literal(&mut self) -> Option<Literal> {
-        if self.lexer.char_at(self.char_index) == Some('"') {
-            let start_index = self.char_index;
-            self.char_index += 1;
-            let mut end_index = self.char_index;
-            let mut escaped = false;
-            while self.char_index < self.lexer.source.grapheme_indices().len().into() {
-                let c = self.lexer.char_at(self.char_index).unwrap();
-                if escaped {
-                    escaped = false;
-                } else if c == '"' {
+        if self.lexer.char_at(self.char_index) != Some('"') {
+            return None;
+        }
+
+        let start_index = self.char_index;
+        self.char_index += 1;
+        loop {
+            match self.lexer.char_at(self.char_index) {
+                Some('"') => {
                     self.char_index += 1;
-                    end_index = self.char_index;
                     break;
-                } else if c == '\\' {
-                    escaped = true;
                 }
+                Some('\\') => self.skip_escape_sequence(),
+                Some(c) if self.lexer.string_recovery && is_line_terminator(c) => {
+                    let span = Span::new(start_index, self.char_index);
+                    self.errors.push(Error::UnterminatedString { span });
+                    break;
+                }
+                Some(_) => self.char_index += 1,
+                None => {
+                    let span = Span::new(start_index, self.char_index);
+                    self.errors.push(Error::UnterminatedString { span });
+                    break;
+                }
+            }
+        }
+        Some(Literal::new_string(Span::new(start_index, self.char_index)))
+    }
+
+    /// Skips an escape sequence inside a string literal, starting at the backslash.
+    /// Illegal escape sequences are recorded as errors.
+    fn skip_escape_sequence(&mut self) {
+        let start_index = self.char_index;
+        self.char_index += 1;
+        let legal = match self.lexer.char_at(self.char_index) {
+            Some('b' | 't' | 'n' | 'f' | 'r' | '"' | '\'' | '\\') => {
                 self.char_index += 1;
+                true
+            }
+            Some(first @ '0'..='7') => {
+                // octal escapes range from \0 to \377
+                let max_digits = if first <= '3' { 3 } else { 2 };
+                let digits = self
+                    .lexer
+                    .count_consecutive_matches(self.char_index, |c| ('0'..='7').contains(&c));
+                self.char_index += digits.min(max_digits);
+                true
+            }
+            // valid unicode escapes are already translated by the source
+            Some('u') => {
+                self.advance_while(|c| c == 'u');
+                let digits = self
+                    .lexer
+                    .count_consecutive_matches(self.char_index, |c| c.is_ascii_hexdigit());
+                self.char_index += digits.min(4);
+                digits >= 4
             }
-            let span = Span::new(start_index, end_index);
-            let literal = Literal::new_string(span);
-            return Some(literal);
+            Some(_) => {
+                self.char_index += 1;
+                false
+            }
+            // reported as an unterminated string
+            None => true,
+        };
+        if !legal {
+            let span = Span::new(start_index, self.char_index);
+            self.errors.push(Error::IllegalEscapeSequence { span });
         }
-        None
     }
 }
 
@@ -232,9 +439,18 @@ impl<'a> Iterator for TokenIterator<'a> {
             return None;
         }
 
-        // check for keyword
-        if let Some(keyword) = self.next_keyword() {
-            return Some(Token::Keyword(keyword));
+        // check for comment, which needs to be checked before operators, since
+        // the start of a comment would otherwise be lexed as a division
+        if let Some(comment) = self.next_line_comment() {
+            return Some(Token::Comment(comment));
+        }
+        if let Some(comment) = self.next_block_comment() {
+            return Some(Token::Comment(comment));
+        }
+
+        // check for keyword, boolean and null literal or identifier
+        if let Some(word) = self.next_word() {
+            return Some(word);
         }
 
         // check for separator
@@ -247,36 +463,28 @@ impl<'a> Iterator for TokenIterator<'a> {
             return Some(Token::Literal(literal));
         }
 
-        // literal needs to be checked before identifier, since a boolean literal like "true" would
-        // otherwise also be a valid identifier
-
-        // check for identifier
-        if let Some(identifier) = self.next_identifier() {
-            return Some(Token::Ident(identifier));
-        }
-
         // check for  operator
         if let Some(operator) = self.next_operator() {
             return Some(Token::Operator(operator));
         }
 
-        // no more tokens found or unknown token
-
-        // TODO: handle unknown/invalid token
-
-        None
+        // the grapheme doesn't start any token, so it is skipped to continue with the next one
+        let span = Span::new(self.char_index, self.char_index + 1);
+        self.char_index += 1;
+        Some(Token::Unknown(span))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::lexer::error::Error;
     use crate::lexer::span::Span;
     use crate::lexer::token::Keyword::*;
     use crate::lexer::token::Separator::{
-        Dot, LeftBracket, LeftCurly, LeftPar, RightBracket, RightCurly, RightPar, Semicolon,
+        At, Dot, LeftBracket, LeftCurly, LeftPar, RightBracket, RightCurly, RightPar, Semicolon,
     };
-    use crate::lexer::token::{Ident, Literal, Operator, Token};
-    use crate::lexer::{is_java_whitespace, Lexer};
+    use crate::lexer::token::{Comment, Ident, Literal, Operator, Radix, Token};
+    use crate::lexer::{is_java_whitespace, Lexer, TokenIterator};
 
     #[test]
     fn test_ident_between_other() {
@@ -293,6 +501,15 @@ mod tests {
         assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
     }
 
+    #[test]
+    fn test_token_text() {
+        let lexer = Lexer::from(r#"return name + "a b";"#);
+        let tokens = lexer.tokens().collect::<Vec<_>>();
+        assert_eq!(lexer.token_text(&tokens[0]), Some("return"));
+        assert_eq!(lexer.token_text(&tokens[1]), Some("name"));
+        assert_eq!(lexer.token_text(&tokens[3]), Some(r#""a b""#));
+    }
+
     #[test]
     fn test_whitespace_definition() {
         assert!(is_java_whitespace(' '));
@@ -392,6 +609,212 @@ volatile while
         assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
     }
 
+    #[test]
+    fn test_keyword_prefix_of_identifier() {
+        let input = "classroom intValue finally1 class Foo";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 9))),
+            Token::Ident(Ident::new(Span::new(10, 18))),
+            Token::Ident(Ident::new(Span::new(19, 27))),
+            Token::Keyword(Class(Span::new(28, 33))),
+            Token::Ident(Ident::new(Span::new(34, 37))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_boolean_prefix_of_identifier() {
+        let input = "trueness falsely return true;";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 8))),
+            Token::Ident(Ident::new(Span::new(9, 16))),
+            Token::Keyword(Return(Span::new(17, 23))),
+            Token::Literal(Literal::new_boolean(Span::new(24, 28))),
+            Token::Separator(Semicolon(Span::new(28, 29))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_unknown_token() {
+        let input = "int x = #;";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Keyword(Int(Span::new(0, 3))),
+            Token::Ident(Ident::new(Span::new(4, 5))),
+            Token::Operator(Operator::Assignment(Span::new(6, 7))),
+            Token::Unknown(Span::new(8, 9)),
+            Token::Separator(Semicolon(Span::new(9, 10))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_long_identifier() {
+        let input = format!("int {};", "a".repeat(100_000));
+        let lexer = Lexer::from(input.as_str());
+        assert_eq!(
+            lexer.count_consecutive_matches(4.into(), |c| c == 'a'),
+            100_000
+        );
+        assert_eq!(
+            lexer.count_consecutive_matches(100_004.into(), |c| c == 'a'),
+            0
+        );
+        assert_eq!(
+            lexer.count_consecutive_matches(200_000.into(), |c| c == 'a'),
+            0
+        );
+        assert_eq!(
+            lexer.tokens().collect::<Vec<_>>(),
+            vec![
+                Token::Keyword(Int(Span::new(0, 3))),
+                Token::Ident(Ident::new(Span::new(4, 100_004))),
+                Token::Separator(Semicolon(Span::new(100_004, 100_005))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_recovery() {
+        let input = "\"abc\nint x;";
+        let lexer = Lexer::from(input).with_string_recovery();
+        let mut tokens = lexer.tokens();
+        assert_eq!(
+            tokens.by_ref().collect::<Vec<_>>(),
+            vec![
+                Token::Literal(Literal::String(Span::new(0, 4))),
+                Token::Keyword(Int(Span::new(5, 8))),
+                Token::Ident(Ident::new(Span::new(9, 10))),
+                Token::Separator(Semicolon(Span::new(10, 11))),
+            ]
+        );
+        assert_eq!(
+            tokens.errors(),
+            &[Error::UnterminatedString {
+                span: Span::new(0, 4)
+            }]
+        );
+
+        // without recovery, the string runs to the end of the input
+        let lexer = Lexer::from(input);
+        assert_eq!(
+            lexer.tokens().collect::<Vec<_>>(),
+            vec![Token::Literal(Literal::String(Span::new(0, 11)))]
+        );
+    }
+
+    #[test]
+    fn test_keyword_heavy_input() {
+        let input =
+            "public static final int a; private volatile boolean b = true;\n".repeat(10_000);
+        let lexer = Lexer::from(input.as_str());
+        let mut counts = [0; 3];
+        for token in lexer.tokens() {
+            match token {
+                Token::Keyword(_) => counts[0] += 1,
+                Token::Ident(_) => counts[1] += 1,
+                Token::Literal(_) => counts[2] += 1,
+                _ => {}
+            }
+        }
+        assert_eq!(counts, [70_000, 20_000, 10_000]);
+    }
+
+    #[test]
+    fn test_identifier_at_end_of_input() {
+        let lexer = Lexer::from("a");
+        let mut tokens = TokenIterator::new(&lexer);
+        assert_eq!(
+            tokens.next_word(),
+            Some(Token::Ident(Ident::new(Span::new(0, 1))))
+        );
+        assert_eq!(tokens.next_word(), None);
+
+        for input in ["class ", "package a."] {
+            let lexer = Lexer::from(input);
+            assert!(lexer.tokens().last().is_some(), "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_line_comment() {
+        let input = "int x; // hi\nint y; //";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Keyword(Int(Span::new(0, 3))),
+            Token::Ident(Ident::new(Span::new(4, 5))),
+            Token::Separator(Semicolon(Span::new(5, 6))),
+            Token::Comment(Comment::new_line(Span::new(7, 12))),
+            Token::Keyword(Int(Span::new(13, 16))),
+            Token::Ident(Ident::new(Span::new(17, 18))),
+            Token::Separator(Semicolon(Span::new(18, 19))),
+            Token::Comment(Comment::new_line(Span::new(20, 22))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_block_comment() {
+        let input = "int /* a\n * /* b\n */ x; /**/";
+        let lexer = Lexer::from(input);
+        let mut tokens = lexer.tokens();
+        let expected = vec![
+            Token::Keyword(Int(Span::new(0, 3))),
+            Token::Comment(Comment::new_block(Span::new(4, 20))),
+            Token::Ident(Ident::new(Span::new(21, 22))),
+            Token::Separator(Semicolon(Span::new(22, 23))),
+            Token::Comment(Comment::new_block(Span::new(24, 28))),
+        ];
+        assert_eq!(tokens.by_ref().collect::<Vec<Token>>(), expected);
+        assert_eq!(tokens.errors(), &[]);
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let input = "x /* a */ */ /* b *";
+        let lexer = Lexer::from(input);
+        let mut tokens = lexer.tokens();
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 1))),
+            Token::Comment(Comment::new_block(Span::new(2, 9))),
+            Token::Operator(Operator::Arithmetic(Span::new(10, 11))),
+            Token::Operator(Operator::Arithmetic(Span::new(11, 12))),
+            Token::Comment(Comment::new_block(Span::new(13, 19))),
+        ];
+        assert_eq!(tokens.by_ref().collect::<Vec<Token>>(), expected);
+        assert_eq!(
+            tokens.errors(),
+            &[Error::UnterminatedComment {
+                span: Span::new(13, 19)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_null_literal() {
+        let input = "Object o = null;";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 6))),
+            Token::Ident(Ident::new(Span::new(7, 8))),
+            Token::Operator(Operator::Assignment(Span::new(9, 10))),
+            Token::Literal(Literal::Null(Span::new(11, 15))),
+            Token::Separator(Semicolon(Span::new(15, 16))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+
+        let lexer = Lexer::from("nullable null1 null_");
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 8))),
+            Token::Ident(Ident::new(Span::new(9, 14))),
+            Token::Ident(Ident::new(Span::new(15, 20))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
     #[test]
     fn test_tokens_simple() {
         let input = r#"
@@ -439,4 +862,367 @@ public static void main(String[] args) {
         ];
         assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
     }
+
+    #[test]
+    fn test_unsigned_right_shift_assignment() {
+        let input = "x >>>= 1;";
+        let lexer = Lexer::from(input);
+        let expected = vec![Token::Operator(Operator::Assignment(Span::new(2, 6)))];
+        assert_eq!(
+            lexer
+                .tokens()
+                .filter(|t| matches!(t, Token::Operator(_)))
+                .collect::<Vec<Token>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_unicode_escapes() {
+        let input = "\\u0041class \\u0063lass";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 6))),
+            Token::Keyword(Class(Span::new(7, 12))),
+        ];
+        let tokens = lexer.tokens().collect::<Vec<Token>>();
+        assert_eq!(tokens, expected);
+        assert_eq!(
+            lexer.source().resolve_span(*tokens[0].span()),
+            Some("\\u0041class")
+        );
+        assert_eq!(
+            lexer.source().resolve_span(*tokens[1].span()),
+            Some("\\u0063lass")
+        );
+    }
+
+    #[test]
+    fn test_resolve_multi_character_operator() {
+        // the combining accent makes grapheme and byte offsets diverge
+        let input = "x\u{0301} >>>= 1;";
+        let lexer = Lexer::from(input);
+        let operator = lexer
+            .tokens()
+            .find(|t| matches!(t, Token::Operator(_)))
+            .expect("operator must be lexed");
+        assert_eq!(*operator.span(), Span::new(2, 6));
+        assert_eq!(lexer.source().resolve_span(*operator.span()), Some(">>>="));
+    }
+
+    #[test]
+    fn test_assignment_operators() {
+        let input = "a = b += c";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 1))),
+            Token::Operator(Operator::Assignment(Span::new(2, 3))),
+            Token::Ident(Ident::new(Span::new(4, 5))),
+            Token::Operator(Operator::Assignment(Span::new(6, 8))),
+            Token::Ident(Ident::new(Span::new(9, 10))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+
+        for input in [
+            "=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<=", ">>=", ">>>=",
+        ] {
+            let lexer = Lexer::from(input);
+            assert_eq!(
+                lexer.tokens().collect::<Vec<Token>>(),
+                vec![Token::Operator(Operator::Assignment(Span::new(
+                    0,
+                    input.len()
+                )))],
+                "{} must be lexed as a single assignment operator",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_logical_operators() {
+        let input = "a && !b || c";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 1))),
+            Token::Operator(Operator::Logical(Span::new(2, 4))),
+            Token::Operator(Operator::Logical(Span::new(5, 6))),
+            Token::Ident(Ident::new(Span::new(6, 7))),
+            Token::Operator(Operator::Logical(Span::new(8, 10))),
+            Token::Ident(Ident::new(Span::new(11, 12))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_increment_and_decrement() {
+        let input = "i++ - --j";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 1))),
+            Token::Operator(Operator::Unary(Span::new(1, 3))),
+            Token::Operator(Operator::Arithmetic(Span::new(4, 5))),
+            Token::Operator(Operator::Unary(Span::new(6, 8))),
+            Token::Ident(Ident::new(Span::new(8, 9))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_not_equal_is_relational() {
+        let input = "a != !b";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 1))),
+            Token::Operator(Operator::Relational(Span::new(2, 4))),
+            Token::Operator(Operator::Logical(Span::new(5, 6))),
+            Token::Ident(Ident::new(Span::new(6, 7))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_bitwise_and_shift_operators() {
+        let input = "x >>> n & mask | y ^ ~z << a >> b";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 1))),
+            Token::Operator(Operator::Shift(Span::new(2, 5))),
+            Token::Ident(Ident::new(Span::new(6, 7))),
+            Token::Operator(Operator::Bitwise(Span::new(8, 9))),
+            Token::Ident(Ident::new(Span::new(10, 14))),
+            Token::Operator(Operator::Bitwise(Span::new(15, 16))),
+            Token::Ident(Ident::new(Span::new(17, 18))),
+            Token::Operator(Operator::Bitwise(Span::new(19, 20))),
+            Token::Operator(Operator::Bitwise(Span::new(21, 22))),
+            Token::Ident(Ident::new(Span::new(22, 23))),
+            Token::Operator(Operator::Shift(Span::new(24, 26))),
+            Token::Ident(Ident::new(Span::new(27, 28))),
+            Token::Operator(Operator::Shift(Span::new(29, 31))),
+            Token::Ident(Ident::new(Span::new(32, 33))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_shift_assignment_is_not_shift() {
+        for (input, expected) in [
+            (
+                "x >>>= y",
+                Token::Operator(Operator::Assignment(Span::new(2, 6))),
+            ),
+            (
+                "x >>= y",
+                Token::Operator(Operator::Assignment(Span::new(2, 5))),
+            ),
+            (
+                "x && y",
+                Token::Operator(Operator::Logical(Span::new(2, 4))),
+            ),
+            (
+                "x &= y",
+                Token::Operator(Operator::Assignment(Span::new(2, 4))),
+            ),
+            (
+                "x || y",
+                Token::Operator(Operator::Logical(Span::new(2, 4))),
+            ),
+        ] {
+            let lexer = Lexer::from(input);
+            assert_eq!(
+                lexer.tokens().nth(1),
+                Some(expected),
+                "unexpected operator token in {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_question_mark() {
+        let input = "a ? b : c";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 1))),
+            Token::Operator(Operator::QuestionMark(Span::new(2, 3))),
+            Token::Ident(Ident::new(Span::new(4, 5))),
+            Token::Operator(Operator::Colon(Span::new(6, 7))),
+            Token::Ident(Ident::new(Span::new(8, 9))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_lambda_arrow() {
+        let input = "x -> x + y - z";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 1))),
+            Token::Operator(Operator::Arrow(Span::new(2, 4))),
+            Token::Ident(Ident::new(Span::new(5, 6))),
+            Token::Operator(Operator::Arithmetic(Span::new(7, 8))),
+            Token::Ident(Ident::new(Span::new(9, 10))),
+            Token::Operator(Operator::Arithmetic(Span::new(11, 12))),
+            Token::Ident(Ident::new(Span::new(13, 14))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_method_reference() {
+        let input = "String::length a : b";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Ident(Ident::new(Span::new(0, 6))),
+            Token::Operator(Operator::DoubleColon(Span::new(6, 8))),
+            Token::Ident(Ident::new(Span::new(8, 14))),
+            Token::Ident(Ident::new(Span::new(15, 16))),
+            Token::Operator(Operator::Colon(Span::new(17, 18))),
+            Token::Ident(Ident::new(Span::new(19, 20))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_string_literal_escapes() {
+        // the unicode escape is translated by the source, so it spans one grapheme
+        let input = r#""line\nbreak" "\"\'\\\t\0\377\u00e9""#;
+        let lexer = Lexer::from(input);
+        let mut tokens = lexer.tokens();
+        let expected = vec![
+            Token::Literal(Literal::new_string(Span::new(0, 13))),
+            Token::Literal(Literal::new_string(Span::new(14, 31))),
+        ];
+        assert_eq!(tokens.by_ref().collect::<Vec<Token>>(), expected);
+        assert_eq!(tokens.errors(), &[]);
+    }
+
+    #[test]
+    fn test_illegal_escape_sequence() {
+        let input = r#""bad\q" x"#;
+        let lexer = Lexer::from(input);
+        let mut tokens = lexer.tokens();
+        let expected = vec![
+            Token::Literal(Literal::new_string(Span::new(0, 7))),
+            Token::Ident(Ident::new(Span::new(8, 9))),
+        ];
+        assert_eq!(tokens.by_ref().collect::<Vec<Token>>(), expected);
+        assert_eq!(
+            tokens.errors(),
+            &[Error::IllegalEscapeSequence {
+                span: Span::new(4, 6)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_string_literal() {
+        let input = r#""a\""#;
+        let lexer = Lexer::from(input);
+        let mut tokens = lexer.tokens();
+        let expected = vec![Token::Literal(Literal::new_string(Span::new(0, 4)))];
+        assert_eq!(tokens.by_ref().collect::<Vec<Token>>(), expected);
+        assert_eq!(
+            tokens.errors(),
+            &[Error::UnterminatedString {
+                span: Span::new(0, 4)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_integer_literals() {
+        let input = "0xDEAD_BEEF 0b1010 0777 1_000 0 0x1Fg 0x 0b_L";
+        let lexer = Lexer::from(input);
+        let mut tokens = lexer.tokens();
+        let expected = vec![
+            Token::Literal(Literal::new_integer(Span::new(0, 11))),
+            Token::Literal(Literal::new_integer(Span::new(12, 18))),
+            Token::Literal(Literal::new_integer(Span::new(19, 23))),
+            Token::Literal(Literal::new_integer(Span::new(24, 29))),
+            Token::Literal(Literal::new_integer(Span::new(30, 31))),
+            Token::Literal(Literal::new_integer(Span::new(32, 36))),
+            Token::Ident(Ident::new(Span::new(36, 37))),
+            Token::Literal(Literal::new_integer(Span::new(38, 40))),
+            Token::Literal(Literal::new_integer(Span::new(41, 45))),
+        ];
+        assert_eq!(tokens.by_ref().collect::<Vec<Token>>(), expected);
+        assert_eq!(
+            tokens.errors(),
+            &[
+                Error::MalformedIntegerLiteral {
+                    span: Span::new(38, 40)
+                },
+                Error::MalformedIntegerLiteral {
+                    span: Span::new(41, 45)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_integer_literals() {
+        let input = "42L 0xFFL 100_000l Long";
+        let lexer = Lexer::from(input);
+        let expected = vec![
+            Token::Literal(Literal::new_integer(Span::new(0, 3))),
+            Token::Literal(Literal::new_integer(Span::new(4, 9))),
+            Token::Literal(Literal::new_integer(Span::new(10, 18))),
+            Token::Ident(Ident::new(Span::new(19, 23))),
+        ];
+        assert_eq!(lexer.tokens().collect::<Vec<Token>>(), expected);
+    }
+
+    #[test]
+    fn test_integer_literal_radix() {
+        for (input, radix) in [
+            ("0xDEAD_BEEF", Radix::Hexadecimal),
+            ("0XFF", Radix::Hexadecimal),
+            ("0b1010", Radix::Binary),
+            ("0B1", Radix::Binary),
+            ("0777", Radix::Octal),
+            ("0", Radix::Decimal),
+            ("0L", Radix::Decimal),
+            ("1_000", Radix::Decimal),
+        ] {
+            assert_eq!(Radix::of(input), radix, "radix of {}", input);
+        }
+    }
+
+    #[test]
+    fn test_at_sign() {
+        let lexer = Lexer::from("@");
+        assert_eq!(
+            lexer.tokens().collect::<Vec<_>>(),
+            vec![Token::Separator(At(Span::new(0, 1)))]
+        );
+
+        let lexer = Lexer::from("@Override");
+        assert_eq!(
+            lexer.tokens().collect::<Vec<_>>(),
+            vec![
+                Token::Separator(At(Span::new(0, 1))),
+                Token::Ident(Ident::new(Span::new(1, 9))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_keyword() {
+        assert!(Lexer::is_keyword("class"));
+        assert!(Lexer::is_keyword("goto"));
+        assert!(!Lexer::is_keyword("Class"));
+        assert!(!Lexer::is_keyword("true"));
+        assert!(!Lexer::is_keyword(""));
+
+        assert!(Lexer::is_reserved_literal("true"));
+        assert!(Lexer::is_reserved_literal("false"));
+        assert!(Lexer::is_reserved_literal("null"));
+        assert!(!Lexer::is_reserved_literal("class"));
+        assert!(!Lexer::is_reserved_literal("NULL"));
+
+        // the free functions are the ones exported from the crate
+        assert!(crate::is_keyword("class"));
+        assert!(crate::is_reserved_literal("null"));
+    }
 }
@@ -1,19 +1,88 @@
 use crate::lexer::span::Span;
 use crate::lexer::GraphemeIndex;
-use core::str::FromStr;
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Source<'a> {
     input: &'a str,
     graphemes: Vec<(usize, char)>,
+    /// The grapheme indices at which lines start, the first one being 0.
+    line_starts: Vec<usize>,
 }
 
 impl<'a> Source<'a> {
+    /// Returns the original input, before unicode escapes are translated.
+    pub fn as_str(&self) -> &'a str {
+        self.input
+    }
+
     pub fn resolve_span(&'a self, span: Span) -> Option<&'a str> {
         self.translate_indices(span.start(), span.end())
     }
 
+    /// Returns the 1-based line and column of the grapheme at the given index.
+    /// A line terminator belongs to the line that it terminates.
+    pub fn line_col(&self, index: GraphemeIndex) -> (usize, usize) {
+        let index = Into::<usize>::into(index);
+        let line = self.line_starts.partition_point(|&start| start <= index);
+        let column = index - self.line_starts[line - 1] + 1;
+        (line, column)
+    }
+
+    /// Like [`Source::line_col`], but the column is the display width of the
+    /// line up to the grapheme, where East Asian wide and fullwidth characters
+    /// take up two columns. This aligns carets in a terminal under lines that
+    /// contain for example CJK text.
+    pub fn display_line_col(&self, index: GraphemeIndex) -> (usize, usize) {
+        let (line, _) = self.line_col(index);
+        // like in line_col, every column past the end of the input counts as one
+        let index = Into::<usize>::into(index);
+        let end = index.min(self.graphemes.len());
+        let width = self.graphemes[self.line_starts[line - 1]..end]
+            .iter()
+            .map(|(_, c)| c.width().unwrap_or(1))
+            .sum::<usize>();
+        (line, width + index - end + 1)
+    }
+
+    /// Returns the run of spaces and tabs at the start of the line that
+    /// contains the start of the span, like `"    "` for a method that is
+    /// indented by four spaces.
+    pub fn leading_indent(&'a self, span: Span) -> &'a str {
+        let (line, _) = self.line_col(span.start());
+        let line_start = self.line_starts[line - 1];
+        let indent_len = self.graphemes[line_start..]
+            .iter()
+            .take_while(|(_, c)| matches!(c, ' ' | '\t'))
+            .count();
+        self.translate_indices(line_start.into(), (line_start + indent_len).into())
+            .unwrap_or_default()
+    }
+
+    /// Returns the line ending that is used by most lines of the source. A
+    /// source without any line ending, as well as a tie, prefers `\n` over
+    /// `\r\n` over `\r`.
+    pub fn line_ending(&self) -> LineEnding {
+        let mut counts = [0_usize; 3];
+        for &line_start in &self.line_starts[1..] {
+            let (offset, c) = self.graphemes[line_start - 1];
+            let ending = match c {
+                '\n' => LineEnding::Lf,
+                _ if self.input[offset..].starts_with("\r\n") => LineEnding::CrLf,
+                _ => LineEnding::Cr,
+            };
+            counts[ending as usize] += 1;
+        }
+        // max_by_key returns the last maximum, so the preferred line endings
+        // have to come last
+        [LineEnding::Lf, LineEnding::CrLf, LineEnding::Cr]
+            .into_iter()
+            .rev()
+            .max_by_key(|ending| counts[*ending as usize])
+            .expect("there are three line endings")
+    }
+
     pub(in crate::lexer) fn grapheme_indices(&self) -> &[(usize, char)] {
         &self.graphemes
     }
@@ -29,23 +98,36 @@ impl<'a> Source<'a> {
         start: GraphemeIndex,
         end: GraphemeIndex,
     ) -> Option<&str> {
-        let start = self.translate_index(start)?;
-        let end = self.translate_index(end - 1_usize)?;
-        self.input.get(start..=end)
+        // a span that ends before it starts is malformed
+        end.checked_sub(start.into())?;
+        let start = self.byte_offset(start)?;
+        let end = self.byte_offset(end)?;
+        self.input.get(start..end)
     }
 
-    pub(in crate::lexer) fn matches(&self, offset: GraphemeIndex, s: &str) -> bool {
-        let mut graphemes = to_graphemes(s);
-        for c in self.graphemes.iter().skip(offset.into()).map(|(_, c)| *c) {
-            let next = graphemes.next();
-            match next {
-                Some(n) if n == c => continue,
-                Some(_) => return false,
-                None => return true,
-            }
+    /// Returns the byte offset of the grapheme at the given index. Unlike
+    /// [`Source::translate_index`], this also accepts the index right after
+    /// the last grapheme, which is the length of the input, since the end of
+    /// a span is exclusive.
+    fn byte_offset(&self, index: GraphemeIndex) -> Option<usize> {
+        match self.translate_index(index) {
+            Some(offset) => Some(offset),
+            None if Into::<usize>::into(index) == self.graphemes.len() => Some(self.input.len()),
+            None => None,
         }
+    }
 
-        graphemes.next().is_none()
+    /// Returns whether the graphemes starting at the given offset are the
+    /// graphemes of `s`. If the input ends before all of `s` is matched, the
+    /// result is `false`.
+    pub(in crate::lexer) fn matches(&self, offset: GraphemeIndex, s: &str) -> bool {
+        let mut remaining = self
+            .graphemes
+            .get(offset.into()..)
+            .unwrap_or_default()
+            .iter()
+            .map(|(_, c)| *c);
+        to_graphemes(s).all(|expected| remaining.next() == Some(expected))
     }
 
     pub(in crate::lexer) fn char_at(&self, index: GraphemeIndex) -> Option<char> {
@@ -55,23 +137,104 @@ impl<'a> Source<'a> {
     }
 }
 
+/// The characters that terminate a line, see [`Source::line_ending`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LineEnding {
+    /// `\n`, as used on Unix.
+    Lf,
+    /// `\r\n`, as used on Windows.
+    CrLf,
+    /// `\r`, as used on classic Mac OS.
+    Cr,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+}
+
 impl<'a> From<&'a str> for Source<'a> {
     fn from(input: &'a str) -> Self {
+        let graphemes = to_grapheme_indices(input);
+        // a \r\n is a single grapheme, so it is only counted once
+        let line_starts = core::iter::once(0)
+            .chain(
+                graphemes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, c))| *c == '\n' || *c == '\r')
+                    .map(|(i, _)| i + 1),
+            )
+            .collect();
         Self {
             input,
-            graphemes: to_grapheme_indices(input),
+            graphemes,
+            line_starts,
         }
     }
 }
 
 fn to_graphemes(s: &str) -> impl Iterator<Item = char> + '_ {
-    UnicodeSegmentation::graphemes(s, true).map(|s| char::from_str(s).unwrap())
+    UnicodeSegmentation::graphemes(s, true).map(first_char)
 }
 
+/// Splits the input into graphemes, where every unicode escape like `\u0041`
+/// is translated into a single grapheme, as Java does before tokenization.
+/// The byte offset of such a grapheme is the offset of the escape's backslash,
+/// so that spans still point at the original text.
 fn to_grapheme_indices(s: &str) -> Vec<(usize, char)> {
-    UnicodeSegmentation::grapheme_indices(s, true)
-        .map(|(i, s)| (i, char::from_str(s).unwrap()))
-        .collect()
+    let mut graphemes = vec![];
+    let mut iter = UnicodeSegmentation::grapheme_indices(s, true).peekable();
+    // a backslash that is preceded by an odd number of backslashes is escaped
+    // itself and thus can't start a unicode escape
+    let mut preceding_backslashes = 0;
+    while let Some((i, grapheme)) = iter.next() {
+        if grapheme == "\\" && preceding_backslashes % 2 == 0 {
+            if let Some((c, len)) = unicode_escape(&s[i..]) {
+                graphemes.push((i, c));
+                while iter.next_if(|(j, _)| *j < i + len).is_some() {}
+                preceding_backslashes = 0;
+                continue;
+            }
+        }
+
+        if grapheme == "\\" {
+            preceding_backslashes += 1;
+        } else {
+            preceding_backslashes = 0;
+        }
+        graphemes.push((i, first_char(grapheme)));
+    }
+    graphemes
+}
+
+/// Decodes the unicode escape at the start of the input, like `\u0041` or
+/// `\uuu0041`, and returns the escaped char and the length of the escape in bytes.
+fn unicode_escape(s: &str) -> Option<(char, usize)> {
+    let rest = s.strip_prefix('\\')?;
+    let digits = rest.trim_start_matches('u');
+    if digits.len() == rest.len() {
+        return None;
+    }
+
+    let hex = digits.get(..4)?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let c = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+    Some((c, s.len() - digits.len() + hex.len()))
+}
+
+/// Returns the first char of a grapheme, which represents the grapheme.
+/// A grapheme can consist of multiple chars, like `\r\n` or a letter
+/// followed by a combining accent.
+fn first_char(grapheme: &str) -> char {
+    grapheme.chars().next().expect("grapheme must not be empty")
 }
 
 #[cfg(test)]
@@ -85,4 +248,136 @@ mod tests {
         assert_eq!(source.translate_indices(7.into(), 12.into()), Some("world"));
         assert_eq!(source.translate_indices(12.into(), 13.into()), Some("!"));
     }
+
+    #[test]
+    fn test_translate_indices_empty_and_end_anchored() {
+        let source = Source::from("int a;");
+        // zero-length spans
+        assert_eq!(source.translate_indices(0.into(), 0.into()), Some(""));
+        assert_eq!(source.translate_indices(3.into(), 3.into()), Some(""));
+        assert_eq!(source.translate_indices(6.into(), 6.into()), Some(""));
+        // the final token
+        assert_eq!(source.translate_indices(5.into(), 6.into()), Some(";"));
+        // a span ending at EOF
+        assert_eq!(source.translate_indices(4.into(), 6.into()), Some("a;"));
+        // reversed
+        assert_eq!(source.translate_indices(4.into(), 2.into()), None);
+        // past the end
+        assert_eq!(source.translate_indices(4.into(), 7.into()), None);
+        assert_eq!(source.translate_indices(7.into(), 7.into()), None);
+
+        let source = Source::from("");
+        assert_eq!(source.translate_indices(0.into(), 0.into()), Some(""));
+    }
+
+    #[test]
+    fn test_line_col() {
+        let source = Source::from("ab\ncd\r\n\nefg\rh");
+        // first line
+        assert_eq!(source.line_col(0.into()), (1, 1));
+        assert_eq!(source.line_col(1.into()), (1, 2));
+        // the newline belongs to the line it terminates
+        assert_eq!(source.line_col(2.into()), (1, 3));
+        assert_eq!(source.line_col(3.into()), (2, 1));
+        // \r\n is a single grapheme
+        assert_eq!(source.line_col(5.into()), (2, 3));
+        assert_eq!(source.line_col(6.into()), (3, 1));
+        assert_eq!(source.line_col(7.into()), (4, 1));
+        assert_eq!(source.line_col(9.into()), (4, 3));
+        assert_eq!(source.line_col(11.into()), (5, 1));
+    }
+
+    #[test]
+    fn test_display_line_col() {
+        // the fullwidth A takes up two columns
+        let source = Source::from("x\n\"\u{FF21}\" + b");
+        assert_eq!(source.line_col(5.into()), (2, 4));
+        assert_eq!(source.display_line_col(5.into()), (2, 5));
+        assert_eq!(source.display_line_col(2.into()), (2, 1));
+        assert_eq!(source.display_line_col(0.into()), (1, 1));
+        // indices past the end of the input don't panic
+        let source = Source::from("ab");
+        assert_eq!(source.line_col(10.into()), (1, 11));
+        assert_eq!(source.display_line_col(10.into()), (1, 11));
+    }
+
+    #[test]
+    fn test_matches_past_end_of_input() {
+        let source = Source::from("cla");
+        assert!(!source.matches(0.into(), "class"));
+        assert!(source.matches(0.into(), "cla"));
+        assert!(source.matches(1.into(), "la"));
+        assert!(!source.matches(3.into(), "a"));
+        assert!(!source.matches(4.into(), "a"));
+        // the empty string matches anywhere, even at the end of the input
+        assert!(source.matches(3.into(), ""));
+    }
+
+    #[test]
+    fn test_as_str() {
+        let input = "class A { String s = \"\\u0041\"; }";
+        let source = Source::from(input);
+        assert_eq!(source.as_str(), input);
+    }
+
+    #[test]
+    fn test_translate_indices_multi_byte() {
+        let source = Source::from("a é ü");
+        assert_eq!(source.translate_indices(2.into(), 3.into()), Some("é"));
+        assert_eq!(source.translate_indices(4.into(), 5.into()), Some("ü"));
+        assert_eq!(source.translate_indices(0.into(), 5.into()), Some("a é ü"));
+    }
+
+    #[test]
+    fn test_unicode_escapes() {
+        let source = Source::from("\\u0041b\\uuu0063");
+        assert_eq!(source.char_at(0.into()), Some('A'));
+        assert_eq!(source.char_at(1.into()), Some('b'));
+        assert_eq!(source.char_at(2.into()), Some('c'));
+        assert_eq!(source.char_at(3.into()), None);
+        assert_eq!(source.resolve_span(Span::new(0, 2)), Some("\\u0041b"));
+        assert_eq!(source.resolve_span(Span::new(2, 3)), Some("\\uuu0063"));
+    }
+
+    #[test]
+    fn test_escaped_unicode_escape() {
+        // an even number of backslashes escapes the backslash, not the unicode escape
+        let source = Source::from("\\\\u0041");
+        assert_eq!(source.grapheme_indices().len(), 7);
+        assert_eq!(source.char_at(2.into()), Some('u'));
+
+        let source = Source::from("\\\\\\u0041");
+        assert_eq!(source.grapheme_indices().len(), 3);
+        assert_eq!(source.char_at(2.into()), Some('A'));
+    }
+
+    #[test]
+    fn test_invalid_unicode_escape() {
+        let source = Source::from("\\u00G1");
+        assert_eq!(source.grapheme_indices().len(), 6);
+        assert_eq!(source.char_at(0.into()), Some('\\'));
+    }
+
+    #[test]
+    fn test_leading_indent() {
+        let input = "class A {\n\t  void f() {\n        g();\n    }\n}";
+        let source = Source::from(input);
+        // the name of the method
+        assert_eq!(source.leading_indent(Span::new(18, 19)), "\t  ");
+        // the call in the method body
+        assert_eq!(source.leading_indent(Span::new(32, 36)), "        ");
+        assert_eq!(source.leading_indent(Span::new(0, 5)), "");
+    }
+
+    #[test]
+    fn test_line_ending() {
+        let source = Source::from("class A {\r\n}\r\n");
+        assert_eq!(source.line_ending(), LineEnding::CrLf);
+        assert_eq!(source.line_ending().as_str(), "\r\n");
+
+        assert_eq!(Source::from("a\nb\r\nc\n").line_ending(), LineEnding::Lf);
+        assert_eq!(Source::from("a\rb\r\nc\r").line_ending(), LineEnding::Cr);
+        assert_eq!(Source::from("a\rb\n").line_ending(), LineEnding::Lf);
+        assert_eq!(Source::from("a").line_ending(), LineEnding::Lf);
+    }
 }
@@ -1,4 +1,19 @@
 use crate::lexer::GraphemeIndex;
+#[cfg(test)]
+use lazy_static::lazy_static;
+#[cfg(test)]
+use std::sync::Mutex;
+
+/// Synthetic spans start at this index, which no real source will reach.
+#[cfg(test)]
+const SYNTHETIC_START: usize = usize::MAX / 2;
+
+#[cfg(test)]
+lazy_static! {
+    /// The texts of synthetic spans, where the index of a text is the offset
+    /// of its span from [`SYNTHETIC_START`].
+    static ref SYNTHETIC_TEXTS: Mutex<Vec<&'static str>> = Mutex::new(vec![]);
+}
 
 pub trait Spanned {
     fn span(&self) -> Option<Span>;
@@ -37,4 +52,29 @@ impl Span {
     pub fn end(&self) -> GraphemeIndex {
         self.end
     }
+
+    /// Creates a span that doesn't point into any source, but carries the given
+    /// text, which [`Span::synthetic_text`] returns. This is useful to build
+    /// tree nodes for comparison in tests without computing offsets.
+    #[cfg(test)]
+    pub fn synthetic(text: &str) -> Self {
+        let mut texts = SYNTHETIC_TEXTS.lock().unwrap();
+        let index = match texts.iter().position(|t| *t == text) {
+            Some(index) => index,
+            None => {
+                // synthetic texts live as long as the program, as spans are Copy
+                texts.push(Box::leak(text.to_string().into_boxed_str()));
+                texts.len() - 1
+            }
+        };
+        Span::new(SYNTHETIC_START + index, SYNTHETIC_START + index + 1)
+    }
+
+    /// Returns the text of a span created with [`Span::synthetic`], or `None`
+    /// if this span points into a source.
+    #[cfg(test)]
+    pub fn synthetic_text(&self) -> Option<&'static str> {
+        let index = Into::<usize>::into(self.start).checked_sub(SYNTHETIC_START)?;
+        SYNTHETIC_TEXTS.lock().unwrap().get(index).copied()
+    }
 }
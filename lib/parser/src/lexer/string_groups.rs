@@ -0,0 +1,82 @@
+use core::iter::Peekable;
+
+use crate::lexer::span::Span;
+use crate::lexer::token::{Literal, Token};
+
+/// A token, or a run of adjacent string literals.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Grouped {
+    Token(Token),
+    /// The spans of string literals that are only separated by whitespace,
+    /// like `"a" "b"`. A single string literal is a group of one.
+    Strings(Vec<Span>),
+}
+
+/// An adaptor over tokens that groups adjacent string literals, for tools
+/// that want to treat them as one conceptual concatenation. Any other token
+/// between two string literals, including a comment, ends the group.
+pub struct StringGroups<I>
+where
+    I: Iterator<Item = Token>,
+{
+    tokens: Peekable<I>,
+}
+
+impl<I> StringGroups<I>
+where
+    I: Iterator<Item = Token>,
+{
+    pub fn new(tokens: I) -> Self {
+        Self {
+            tokens: tokens.peekable(),
+        }
+    }
+}
+
+impl<I> Iterator for StringGroups<I>
+where
+    I: Iterator<Item = Token>,
+{
+    type Item = Grouped;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut strings = vec![];
+        while let Some(Token::Literal(Literal::String(span))) = self
+            .tokens
+            .next_if(|t| matches!(t, Token::Literal(Literal::String(_))))
+        {
+            strings.push(span);
+        }
+
+        if strings.is_empty() {
+            self.tokens.next().map(Grouped::Token)
+        } else {
+            Some(Grouped::Strings(strings))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::{Ident, Separator};
+    use crate::lexer::Lexer;
+
+    #[test]
+    fn test_adjacent_strings_are_grouped() {
+        let lexer = Lexer::from(r#"f("a" "b"  "c" d "e");"#);
+        let groups = lexer.tokens().group_strings().collect::<Vec<_>>();
+        assert_eq!(
+            groups,
+            vec![
+                Grouped::Token(Token::Ident(Ident::new(Span::new(0, 1)))),
+                Grouped::Token(Token::Separator(Separator::LeftPar(Span::new(1, 2)))),
+                Grouped::Strings(vec![Span::new(2, 5), Span::new(6, 9), Span::new(11, 14)]),
+                Grouped::Token(Token::Ident(Ident::new(Span::new(15, 16)))),
+                Grouped::Strings(vec![Span::new(17, 20)]),
+                Grouped::Token(Token::Separator(Separator::RightPar(Span::new(20, 21)))),
+                Grouped::Token(Token::Separator(Separator::Semicolon(Span::new(21, 22)))),
+            ]
+        );
+    }
+}
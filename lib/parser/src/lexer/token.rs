@@ -9,7 +9,7 @@ macro_rules! count {
 macro_rules! constant_collection {
     ($collection:ident : $($ident:ident = $value:literal),*,) => {
         $(
-        const $ident: &'static str = $value;
+        pub(in crate::lexer) const $ident: &'static str = $value;
         )*
 
 
@@ -23,9 +23,8 @@ macro_rules! constant_collection {
 }
 
 constant_collection! {
-    // These are sorted, so that e.g. 'double' comes before 'do', so that if we
-    // check for all keywords using this array, we don't run into prefix-related
-    // problems. Other than that, this array is sorted alphabetically.
+    // Keywords are lexed by reading a whole word and matching it against these
+    // constants, so unlike operators, their order doesn't matter.
     KEYWORDS:
     KEYWORD_ABSTRACT = "abstract",
     KEYWORD_ASSERT = "assert",
@@ -90,8 +89,11 @@ constant_collection! {
     SEPARATOR_RIGHT_CURLY = "}",
     SEPARATOR_LEFT_BRACKET = "[",
     SEPARATOR_RIGHT_BRACKET = "]",
+    SEPARATOR_AT = "@",
 }
 
+pub(in crate::lexer) const NULL_LITERAL: &str = "null";
+
 constant_collection! {
     BOOLEAN_VALUES:
     BOOLEAN_TRUE = "true",
@@ -99,11 +101,49 @@ constant_collection! {
 }
 
 constant_collection! {
-    ARITHMETIC_OPERATOR_VALUES:
+    // These are sorted by length, so that the longest operator is tried first
+    // (maximal munch). Otherwise '>>>=' would for example be lexed as '>>',
+    // '>' and '=' once shift and relational operators are supported.
+    OPERATORS:
+    OPERATOR_UNSIGNED_RIGHT_SHIFT_ASSIGN = ">>>=",
+    OPERATOR_LEFT_SHIFT_ASSIGN = "<<=",
+    OPERATOR_RIGHT_SHIFT_ASSIGN = ">>=",
+    OPERATOR_UNSIGNED_RIGHT_SHIFT = ">>>",
+    OPERATOR_PLUS_ASSIGN = "+=",
+    OPERATOR_MINUS_ASSIGN = "-=",
+    OPERATOR_MULTIPLY_ASSIGN = "*=",
+    OPERATOR_DIVIDE_ASSIGN = "/=",
+    OPERATOR_MODULO_ASSIGN = "%=",
+    OPERATOR_AND_ASSIGN = "&=",
+    OPERATOR_OR_ASSIGN = "|=",
+    OPERATOR_XOR_ASSIGN = "^=",
+    OPERATOR_EQUAL = "==",
+    OPERATOR_NOT_EQUAL = "!=",
+    OPERATOR_LESS_EQUAL = "<=",
+    OPERATOR_GREATER_EQUAL = ">=",
+    OPERATOR_AND = "&&",
+    OPERATOR_OR = "||",
+    OPERATOR_LEFT_SHIFT = "<<",
+    OPERATOR_RIGHT_SHIFT = ">>",
+    OPERATOR_INCREMENT = "++",
+    OPERATOR_DECREMENT = "--",
+    OPERATOR_ARROW = "->",
+    OPERATOR_DOUBLE_COLON = "::",
+    OPERATOR_ASSIGN = "=",
+    OPERATOR_LESS = "<",
+    OPERATOR_GREATER = ">",
+    OPERATOR_NOT = "!",
+    OPERATOR_BITWISE_AND = "&",
+    OPERATOR_BITWISE_OR = "|",
+    OPERATOR_BITWISE_XOR = "^",
+    OPERATOR_BITWISE_COMPLEMENT = "~",
+    OPERATOR_QUESTION_MARK = "?",
+    OPERATOR_COLON = ":",
     OPERATOR_PLUS = "+",
     OPERATOR_MINUS = "-",
     OPERATOR_MULTIPLY = "*",
     OPERATOR_DIVIDE = "/",
+    OPERATOR_MODULO = "%",
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -114,6 +154,8 @@ pub enum Token {
     Operator(Operator),
     Separator(Separator),
     Comment(Comment),
+    /// A grapheme that doesn't start any token, like `#`.
+    Unknown(Span),
 }
 
 impl Token {
@@ -125,6 +167,25 @@ impl Token {
             Token::Operator(operator) => operator.span(),
             Token::Separator(separator) => separator.span(),
             Token::Comment(comment) => comment.span(),
+            Token::Unknown(span) => span,
+        }
+    }
+
+    /// Returns whether this token is of the same kind as the other token,
+    /// ignoring their spans. For keywords, literals, operators, separators
+    /// and comments, the specific variant must also match, so two `public`
+    /// keywords are of the same kind, but `public` and `private` are not.
+    pub fn same_kind(&self, other: &Token) -> bool {
+        use core::mem::discriminant;
+
+        match (self, other) {
+            (Token::Ident(_), Token::Ident(_)) | (Token::Unknown(_), Token::Unknown(_)) => true,
+            (Token::Keyword(a), Token::Keyword(b)) => discriminant(a) == discriminant(b),
+            (Token::Literal(a), Token::Literal(b)) => discriminant(a) == discriminant(b),
+            (Token::Operator(a), Token::Operator(b)) => discriminant(a) == discriminant(b),
+            (Token::Separator(a), Token::Separator(b)) => discriminant(a) == discriminant(b),
+            (Token::Comment(a), Token::Comment(b)) => discriminant(a) == discriminant(b),
+            _ => false,
         }
     }
 }
@@ -169,6 +230,27 @@ macro_rules! token_type {
                     $(Self::$name(_) => stringify!($name)),*
                 }
             }
+
+            /// The position of this variant in the declaration of the type.
+            #[cfg(feature = "token-cache")]
+            pub(in crate::lexer) fn index(&self) -> u8 {
+                // a fieldless copy of the variants, whose discriminants are
+                // their positions
+                enum Index {
+                    $($name),*
+                }
+                match self {
+                    $(Self::$name(_) => Index::$name as u8),*
+                }
+            }
+
+            /// Creates the variant at the given position in the declaration
+            /// of the type, or `None` if there is no such variant.
+            #[cfg(feature = "token-cache")]
+            pub(in crate::lexer) fn from_index(index: u8, span: Span) -> Option<Self> {
+                let variants: &[fn(Span) -> Self] = &[$(Self::$name),*];
+                variants.get(usize::from(index)).map(|variant| variant(span))
+            }
         }
 
         impl core::fmt::Display for $token_type {
@@ -180,11 +262,11 @@ macro_rules! token_type {
 }
 
 macro_rules! try_from_str {
-    ($ty:ident: $($name:ident: $value:expr),*,) => {
+    ($ty:ident: $($name:ident: $value:ident),*,) => {
         impl<'a> $ty {
             pub fn try_from_str(s: &'a str, span:Span) -> Option<Self> {
                 match s {
-                    $(x if x == $value => Some(Self::$name(span))),*,
+                    $($value => Some(Self::$name(span)),)*
                     _ => None,
                 }
             }
@@ -307,6 +389,28 @@ token_type! {
     Character: new_character,
     String: new_string,
     Boolean: new_boolean,
+    Null: new_null,
+}
+
+/// The radix of an integer literal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Radix {
+    Binary,
+    Octal,
+    Decimal,
+    Hexadecimal,
+}
+
+impl Radix {
+    /// Determines the radix of the given integer literal text, like `0x1F`.
+    pub fn of(literal: &str) -> Self {
+        match literal.as_bytes() {
+            [b'0', b'x' | b'X', ..] => Radix::Hexadecimal,
+            [b'0', b'b' | b'B', ..] => Radix::Binary,
+            [b'0', b'0'..=b'9' | b'_', ..] => Radix::Octal,
+            _ => Radix::Decimal,
+        }
+    }
 }
 
 token_type! {
@@ -320,6 +424,8 @@ token_type! {
     Shift: new_shift,
     QuestionMark: new_question_mark,
     Colon: new_colon,
+    Arrow: new_arrow,
+    DoubleColon: new_double_colon,
 }
 
 try_from_str! {
@@ -328,6 +434,41 @@ try_from_str! {
     Arithmetic: OPERATOR_MINUS,
     Arithmetic: OPERATOR_MULTIPLY,
     Arithmetic: OPERATOR_DIVIDE,
+    Arithmetic: OPERATOR_MODULO,
+    Assignment: OPERATOR_ASSIGN,
+    Assignment: OPERATOR_PLUS_ASSIGN,
+    Assignment: OPERATOR_MINUS_ASSIGN,
+    Assignment: OPERATOR_MULTIPLY_ASSIGN,
+    Assignment: OPERATOR_DIVIDE_ASSIGN,
+    Assignment: OPERATOR_MODULO_ASSIGN,
+    Assignment: OPERATOR_AND_ASSIGN,
+    Assignment: OPERATOR_OR_ASSIGN,
+    Assignment: OPERATOR_XOR_ASSIGN,
+    Assignment: OPERATOR_LEFT_SHIFT_ASSIGN,
+    Assignment: OPERATOR_RIGHT_SHIFT_ASSIGN,
+    Assignment: OPERATOR_UNSIGNED_RIGHT_SHIFT_ASSIGN,
+    Relational: OPERATOR_EQUAL,
+    Relational: OPERATOR_NOT_EQUAL,
+    Relational: OPERATOR_LESS,
+    Relational: OPERATOR_GREATER,
+    Relational: OPERATOR_LESS_EQUAL,
+    Relational: OPERATOR_GREATER_EQUAL,
+    Logical: OPERATOR_AND,
+    Logical: OPERATOR_OR,
+    Logical: OPERATOR_NOT,
+    Bitwise: OPERATOR_BITWISE_AND,
+    Bitwise: OPERATOR_BITWISE_OR,
+    Bitwise: OPERATOR_BITWISE_XOR,
+    Bitwise: OPERATOR_BITWISE_COMPLEMENT,
+    Shift: OPERATOR_LEFT_SHIFT,
+    Shift: OPERATOR_RIGHT_SHIFT,
+    Shift: OPERATOR_UNSIGNED_RIGHT_SHIFT,
+    Unary: OPERATOR_INCREMENT,
+    Unary: OPERATOR_DECREMENT,
+    QuestionMark: OPERATOR_QUESTION_MARK,
+    Colon: OPERATOR_COLON,
+    Arrow: OPERATOR_ARROW,
+    DoubleColon: OPERATOR_DOUBLE_COLON,
 }
 
 token_type! {
@@ -341,6 +482,7 @@ token_type! {
     RightCurly: new_right_curly,
     LeftBracket: new_left_bracket,
     RightBracket: new_right_bracket,
+    At: new_at,
 }
 
 try_from_str! {
@@ -354,6 +496,7 @@ try_from_str! {
     RightCurly: SEPARATOR_RIGHT_CURLY,
     LeftBracket: SEPARATOR_LEFT_BRACKET,
     RightBracket: SEPARATOR_RIGHT_BRACKET,
+    At: SEPARATOR_AT,
 }
 
 token_type! {
@@ -361,3 +504,22 @@ token_type! {
     Line: new_line,
     Block: new_block,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_kind() {
+        let public_a = Token::Keyword(Keyword::Public(Span::new(0, 6)));
+        let public_b = Token::Keyword(Keyword::Public(Span::new(10, 16)));
+        let private = Token::Keyword(Keyword::Private(Span::new(0, 7)));
+        let ident = Token::Ident(Ident::new(Span::new(0, 6)));
+
+        assert!(public_a.same_kind(&public_b));
+        assert!(public_b.same_kind(&public_a));
+        assert!(!public_a.same_kind(&private));
+        assert!(!public_a.same_kind(&ident));
+        assert!(ident.same_kind(&Token::Ident(Ident::new(Span::new(3, 4)))));
+    }
+}
@@ -1,5 +1,7 @@
+pub use crate::lexer::source::{LineEnding, Source};
+pub use crate::lexer::{is_keyword, is_reserved_literal};
 pub use crate::parser::tree::*;
-pub use crate::parser::Parser;
+pub use crate::parser::{LiteralOverflowPolicy, Parser};
 
 mod lexer;
 mod parser;
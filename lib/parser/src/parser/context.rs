@@ -1,22 +1,46 @@
-use crate::lexer::token::{Keyword, Operator, Separator, Token};
+use crate::lexer::source::Source;
+use crate::lexer::span::{Span, Spanned};
+use crate::lexer::token::{Keyword, Literal, Operator, Radix, Separator, Token};
+use crate::lexer::GraphemeIndex;
 use crate::parser::error::Error;
+use crate::parser::token_stream::TokenStream;
+use crate::parser::tree::Block;
 use crate::parser::tree::Identifier;
 use crate::parser::tree::QualifiedName;
 use crate::parser::tree::Visibility;
-use crate::parser::Result;
+use crate::parser::{LiteralOverflowPolicy, Result};
 use crate::{
-    ClassDeclaration, ClassMember, ClassModifiers, CompilationUnit, ImportDeclaration, Parser,
-    TypeDeclaration,
+    Annotation, AnnotationDeclaration, AnnotationElement, AnnotationMember, AnnotationModifiers,
+    AnonymousClassBody, ArrayAccess, ArrayCreation, ArrayElement, ArrayInitializer,
+    AssertStatement, Assignment, Binary, BooleanLiteral, BreakStatement, CaseLabel, Cast,
+    CatchClause, CatchParameter, ClassDeclaration, ClassMember, ClassModifiers, CompilationUnit,
+    Conditional, ConstructorDeclaration, ContinueStatement, DoWhileStatement, ElementValue,
+    ElementValueArray, ElementValuePair, ElseIfStatement, EnumDeclaration, EnumMember,
+    EnumModifiers, Expression, FieldAccess, FieldDeclaration, FieldDeclarationPart, FieldModifiers,
+    ForEachStatement, ForEachVariableDeclaration, ForInitializer, ForStatement, IfStatement,
+    ImportDeclaration, InstanceOf, IntegerLiteral, InterfaceDeclaration, InterfaceMember,
+    InterfaceModifiers, LocalVariableDeclaration, LocalVariableDeclarationPart, MethodCall,
+    MethodDeclaration, MethodModifiers, NullLiteral, ObjectCreation, Parameter, ParameterModifiers,
+    PrimitiveKind, ReceiverParameter, Statement, StatementKind, StringLiteral, SwitchCase,
+    SwitchStatement, SynchronizedStatement, This, TryStatement, Type, TypeArgument,
+    TypeDeclaration, TypeParameter, Unary, Wildcard, WildcardBound,
 };
-use std::iter::Peekable;
+use std::collections::BTreeMap;
 
 pub(in crate::parser) struct ParseContext<'a, I>
 where
     I: Iterator<Item = Token>,
 {
-    parser: &'a Parser<'a>,
+    /// The source that the tokens point into, which is used to resolve their text.
+    source: &'a Source<'a>,
+    literal_overflow: LiteralOverflowPolicy,
     compilation_unit: CompilationUnit,
-    tokens: Peekable<I>,
+    tokens: TokenStream<I>,
+    /// The Javadoc comments, keyed by the start of the token that directly follows them.
+    doc_comments: BTreeMap<GraphemeIndex, Span>,
+    /// The names of the local variables declared so far in each enclosing
+    /// block, with the innermost block last.
+    local_scopes: Vec<Vec<&'a str>>,
 }
 
 impl<I> From<ParseContext<'_, I>> for CompilationUnit
@@ -33,17 +57,28 @@ where
     I: Iterator<Item = Token>,
 {
     pub fn new(
-        parser: &'a Parser<'a>,
+        source: &'a Source<'a>,
         compilation_unit: CompilationUnit,
-        tokens: Peekable<I>,
+        tokens: TokenStream<I>,
     ) -> Self {
         Self {
-            parser,
+            source,
+            literal_overflow: LiteralOverflowPolicy::default(),
             compilation_unit,
             tokens,
+            doc_comments: BTreeMap::new(),
+            local_scopes: vec![],
         }
     }
 
+    pub fn set_doc_comments(&mut self, doc_comments: BTreeMap<GraphemeIndex, Span>) {
+        self.doc_comments = doc_comments;
+    }
+
+    pub fn set_literal_overflow_policy(&mut self, policy: LiteralOverflowPolicy) {
+        self.literal_overflow = policy;
+    }
+
     pub fn parse(&mut self) {
         self.compilation_unit();
     }
@@ -64,6 +99,86 @@ where
         }
     }
 
+    /// Consumes the next token if it matches, otherwise returns an
+    /// [`Error::UnexpectedToken`] without consuming anything.
+    ///
+    /// As opposed to [`ParseContext::expect_token`], the error is not added to
+    /// the compilation unit, but returned to the caller.
+    fn require_token<F>(&mut self, expected: &'static [&'static str], f: F) -> Result<Token>
+    where
+        F: FnOnce(&I::Item) -> bool,
+    {
+        match self.tokens.next_if(f) {
+            Some(t) => Ok(t),
+            None => Err(Error::UnexpectedToken {
+                expected,
+                found: self.tokens.peek().cloned(),
+            }),
+        }
+    }
+
+    /// Returns whether the given token is an operator with the given text.
+    fn is_operator(&self, token: &Token, operator: &str) -> bool {
+        matches!(token, Token::Operator(_))
+            && self.source.resolve_span(*token.span()) == Some(operator)
+    }
+
+    /// Returns whether the `n`-th next token is an operator with the given text.
+    fn peek_nth_is_operator(&mut self, n: usize, operator: &str) -> bool {
+        match self.tokens.peek_nth(n).cloned() {
+            Some(token) => self.is_operator(&token, operator),
+            None => false,
+        }
+    }
+
+    /// Consumes the next token if it is an operator with the given text.
+    fn next_if_operator(&mut self, operator: &str) -> Option<Token> {
+        if self.peek_nth_is_operator(0, operator) {
+            self.tokens.next()
+        } else {
+            None
+        }
+    }
+
+    /// Consumes the next token if it is an operator with one of the given
+    /// texts, otherwise returns an [`Error::UnexpectedToken`].
+    fn require_operator(&mut self, expected: &'static [&'static str]) -> Result<Token> {
+        match expected.iter().find_map(|op| self.next_if_operator(op)) {
+            Some(token) => Ok(token),
+            None => Err(Error::UnexpectedToken {
+                expected,
+                found: self.tokens.peek().cloned(),
+            }),
+        }
+    }
+
+    /// Consumes a `>` that closes type arguments or type parameters.
+    ///
+    /// The lexer reads `>>` and `>>>` as shift operators, but in a type like
+    /// `Map<K, List<V>>`, they close multiple scopes. Such a token is split:
+    /// its first `>` is consumed, and the rest is put back for the enclosing
+    /// scopes.
+    fn require_closing_angle_bracket(&mut self) -> Result<Token> {
+        let shift = match self.next_if_operator(">>") {
+            Some(token) => Some(token),
+            None => self.next_if_operator(">>>"),
+        };
+        let Some(shift) = shift else {
+            return self.require_operator(&[">"]);
+        };
+        let span = *shift.span();
+        let rest = Span::new(span.start() + 1, span.end());
+        let rest = match self.source.resolve_span(rest) {
+            Some(">") => Operator::new_relational(rest),
+            _ => Operator::new_shift(rest),
+        };
+        self.tokens.push_front(Token::Operator(rest));
+        Ok(Token::Operator(Operator::new_relational(Span::new(
+            span.start(),
+            span.start() + 1,
+        ))))
+    }
+
     /// Peeks one token, and consumes it if it is a semicolon.
     ///
     /// If the token is not a semicolon, an error is added to the compilation unit.
@@ -91,16 +206,76 @@ where
                     self.expect_semicolon();
                 }
                 _ => match self.type_declaration() {
-                    Ok(type_decl) => self.compilation_unit.add_type(type_decl),
-                    Err(error) => self.compilation_unit.add_error(error),
+                    Ok(type_decl) => {
+                        self.check_public_type(&type_decl);
+                        self.compilation_unit.add_type(type_decl)
+                    }
+                    Err(error) => {
+                        self.compilation_unit.add_error(error);
+                        self.skip_member();
+                        // a stray closing curly brace is not consumed by skip_member
+                        self.tokens
+                            .next_if(|t| matches!(t, Token::Separator(Separator::RightCurly(_))));
+                    }
                 },
             }
         }
     }
 
+    /// Warns about a public top-level type if an earlier one is public too,
+    /// since only one of them can match the name of the file.
+    fn check_public_type(&mut self, type_decl: &TypeDeclaration) {
+        let is_public = |ty: &TypeDeclaration| ty.visibility().contains(Visibility::Public);
+        if is_public(type_decl) && self.compilation_unit.types().iter().any(is_public) {
+            self.compilation_unit
+                .add_warning(Error::MultiplePublicTypes {
+                    span: *type_decl.name().span(),
+                });
+        }
+    }
+
     fn type_declaration(&mut self) -> Result<TypeDeclaration> {
+        let doc_comment = self.doc_comment();
         let visibility = self.visibility()?;
         let class_modifiers = self.class_modifiers()?;
+        // interfaces, enums and annotation types can only be static, the other
+        // class modifiers are dropped for them
+        let is_static = class_modifiers.contains(ClassModifiers::Static);
+        let mut declaration = match self.tokens.peek() {
+            Some(Token::Separator(Separator::At(_))) => {
+                let mut modifiers = AnnotationModifiers::empty();
+                modifiers.set(AnnotationModifiers::Static, is_static);
+                self.annotation_declaration(visibility, modifiers)
+            }
+            Some(Token::Keyword(Keyword::Interface(_))) => {
+                let mut modifiers = InterfaceModifiers::empty();
+                modifiers.set(InterfaceModifiers::Static, is_static);
+                self.interface_declaration(visibility, modifiers)
+            }
+            Some(Token::Keyword(Keyword::Enum(_))) => {
+                let mut modifiers = EnumModifiers::empty();
+                modifiers.set(EnumModifiers::Static, is_static);
+                self.enum_declaration(visibility, modifiers)
+            }
+            _ => self.class_declaration(visibility, class_modifiers),
+        }?;
+        if let Some(doc_comment) = doc_comment {
+            declaration.set_doc_comment(doc_comment);
+        }
+        Ok(declaration)
+    }
+
+    /// Returns the Javadoc comment that directly precedes the next token.
+    fn doc_comment(&mut self) -> Option<Span> {
+        let start = self.tokens.peek()?.span().start();
+        self.doc_comments.get(&start).copied()
+    }
+
+    fn class_declaration(
+        &mut self,
+        visibility: Visibility,
+        modifiers: ClassModifiers,
+    ) -> Result<TypeDeclaration> {
         match self
             .tokens
             .next_if(|t| matches!(t, Token::Keyword(Keyword::Class(_))))
@@ -114,281 +289,4007 @@ where
             }
         };
         let name = self.identifier()?;
-        let mut class_declaration = ClassDeclaration::new(visibility, class_modifiers, name);
+        let mut class_declaration = ClassDeclaration::new(visibility, modifiers, name);
+        for type_parameter in self.optional_type_parameters()? {
+            class_declaration.add_type_parameter(type_parameter);
+        }
 
-        // TODO: extends, implements
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Extends(_))))
+            .is_some()
+        {
+            class_declaration.set_extends(self.r#type()?);
+        }
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Implements(_))))
+            .is_some()
+        {
+            loop {
+                class_declaration.add_implements(self.r#type()?);
+                if self
+                    .tokens
+                    .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                    .is_none()
+                {
+                    break;
+                }
+            }
+        }
 
         self.expect_token(&["{"], |t| {
             matches!(t, Token::Separator(Separator::LeftCurly(_)))
         });
-
-        while let None = self
-            .tokens
-            .next_if(|t| matches!(t, Token::Separator(Separator::RightCurly(_))))
-        {
-            match self.class_member() {
-                Ok(member) => class_declaration.add_member(member),
-                Err(e) => self.compilation_unit.add_error(e),
-            };
+        for member in self.members(Self::class_member) {
+            class_declaration.add_member(member);
         }
 
         Ok(TypeDeclaration::Class(class_declaration))
     }
 
-    fn class_member(&mut self) -> Result<ClassMember> {
-        let visibility = self.visibility()?;
-        // TODO: modifiers
+    fn interface_declaration(
+        &mut self,
+        visibility: Visibility,
+        modifiers: InterfaceModifiers,
+    ) -> Result<TypeDeclaration> {
+        let interface_token = self.tokens.next().unwrap(); // skip the interface token
+        debug_assert!(matches!(
+            interface_token,
+            Token::Keyword(Keyword::Interface(_))
+        ));
+
         let name = self.identifier()?;
-        self.expect_token(&["("], |t| {
-            matches!(t, Token::Separator(Separator::LeftParen(_)))
-        });
-        // TODO: parameters
-        self.expect_token(&[")"], |t| {
-            matches!(t, Token::Separator(Separator::RightParen(_)))
-        });
+        let mut interface_declaration = InterfaceDeclaration::new(visibility, modifiers, name);
+        for type_parameter in self.optional_type_parameters()? {
+            interface_declaration.add_type_parameter(type_parameter);
+        }
+
+        // TODO: extends
+
         self.expect_token(&["{"], |t| {
             matches!(t, Token::Separator(Separator::LeftCurly(_)))
         });
-        // TODO: block
-        self.expect_token(&["}"], |t| {
-            matches!(t, Token::Separator(Separator::RightCurly(_)))
-        });
+        for member in self.members(Self::interface_member) {
+            interface_declaration.add_member(member);
+        }
 
-        Err(Error::NotImplemented(None))
+        Ok(TypeDeclaration::Interface(interface_declaration))
     }
 
-    fn identifier(&mut self) -> Result<Identifier> {
-        match self.tokens.next_if(|t| matches!(t, Token::Ident(_))) {
-            Some(Token::Ident(id)) => Ok(Identifier::from(id)),
-            v @ _ => Err(Error::UnexpectedToken {
-                expected: &["identifier"],
-                found: v,
-            }),
+    /// Parses an annotation type declaration like `@interface A { String value(); }`.
+    fn annotation_declaration(
+        &mut self,
+        visibility: Visibility,
+        modifiers: AnnotationModifiers,
+    ) -> Result<TypeDeclaration> {
+        let at_token = self.tokens.next().unwrap(); // skip the @ token
+        debug_assert!(matches!(at_token, Token::Separator(Separator::At(_))));
+        self.require_token(&["interface"], |t| {
+            matches!(t, Token::Keyword(Keyword::Interface(_)))
+        })?;
+
+        let name = self.identifier()?;
+        let mut annotation_declaration = AnnotationDeclaration::new(visibility, modifiers, name);
+
+        self.expect_token(&["{"], |t| {
+            matches!(t, Token::Separator(Separator::LeftCurly(_)))
+        });
+        for member in self.members(Self::annotation_member) {
+            annotation_declaration.add_member(member);
         }
+
+        Ok(TypeDeclaration::Annotation(annotation_declaration))
     }
 
-    fn visibility(&mut self) -> Result<Visibility> {
-        let mut vis = Visibility::empty();
+    fn enum_declaration(
+        &mut self,
+        visibility: Visibility,
+        modifiers: EnumModifiers,
+    ) -> Result<TypeDeclaration> {
+        let enum_token = self.tokens.next().unwrap(); // skip the enum token
+        debug_assert!(matches!(enum_token, Token::Keyword(Keyword::Enum(_))));
 
-        while let Some(token) = self.tokens.next_if(|t| {
-            matches!(
-                t,
-                Token::Keyword(Keyword::Public(_))
-                    | Token::Keyword(Keyword::Protected(_))
-                    | Token::Keyword(Keyword::Private(_))
-            )
-        }) {
-            match token {
-                Token::Keyword(Keyword::Public(_)) => vis.insert(Visibility::Public),
-                Token::Keyword(Keyword::Protected(_)) => vis.insert(Visibility::Protected),
-                Token::Keyword(Keyword::Private(_)) => vis.insert(Visibility::Private),
-                _ => unreachable!(),
+        let name = self.identifier()?;
+        let mut enum_declaration = EnumDeclaration::new(visibility, modifiers, name);
+
+        // TODO: implements
+
+        self.expect_token(&["{"], |t| {
+            matches!(t, Token::Separator(Separator::LeftCurly(_)))
+        });
+
+        // the enum constants come first, separated by commas
+        while let Some(Token::Ident(ident)) = self.tokens.next_if(|t| matches!(t, Token::Ident(_)))
+        {
+            enum_declaration.add_member(EnumMember::EnumConstant(Identifier::from(ident)));
+            if self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                .is_none()
+            {
+                break;
             }
         }
 
-        Ok(vis)
+        // the constants may be followed by a semicolon and other members
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::Semicolon(_))))
+            .is_some()
+        {
+            for member in self.members(Self::enum_member) {
+                enum_declaration.add_member(member);
+            }
+        } else {
+            self.expect_token(&["}"], |t| {
+                matches!(t, Token::Separator(Separator::RightCurly(_)))
+            });
+        }
+
+        Ok(TypeDeclaration::Enum(enum_declaration))
     }
 
-    fn class_modifiers(&mut self) -> Result<ClassModifiers> {
-        let mut mods = ClassModifiers::empty();
+    /// Parses members with the given rule until the closing `}` of the
+    /// body, which is consumed. The opening `{` must already be consumed.
+    ///
+    /// Members that fail to parse are skipped, and their errors are added
+    /// to the compilation unit as [`Error::InvalidMember`], pointing at the
+    /// first token of the member.
+    fn members<T, F>(&mut self, mut member: F) -> Vec<T>
+    where
+        F: FnMut(&mut Self) -> Result<T>,
+    {
+        let mut members = vec![];
+        while self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::RightCurly(_))))
+            .is_none()
+        {
+            if self.tokens.peek().is_none() {
+                self.compilation_unit
+                    .add_error(Error::UnexpectedEOF { expected: &["}"] });
+                break;
+            }
+            members.extend(self.recovering_member(&mut member));
+        }
+        members
+    }
 
-        while let Some(token) = self.tokens.next_if(|t| {
-            matches!(
-                t,
-                Token::Keyword(Keyword::Abstract(_))
-                    | Token::Keyword(Keyword::Final(_))
-                    | Token::Keyword(Keyword::Static(_))
-            )
-        }) {
-            match token {
-                Token::Keyword(Keyword::Abstract(_)) => mods.insert(ClassModifiers::Abstract),
-                Token::Keyword(Keyword::Final(_)) => mods.insert(ClassModifiers::Final),
-                Token::Keyword(Keyword::Static(_)) => mods.insert(ClassModifiers::Static),
-                _ => unreachable!(),
+    /// Parses class members until the end of the input, for a fragment that
+    /// only consists of members without the enclosing class. Errors are
+    /// handled like in [`ParseContext::members`].
+    pub fn member_fragment(&mut self) -> Vec<ClassMember> {
+        let mut members = vec![];
+        while let Some(token) = self.tokens.peek() {
+            // there is no body that a stray `}` could close, and skip_member
+            // would not consume it
+            if let Token::Separator(Separator::RightCurly(_)) = token {
+                let found = self.tokens.next();
+                self.compilation_unit.add_error(Error::UnexpectedToken {
+                    expected: &["member"],
+                    found,
+                });
+                continue;
             }
+            members.extend(self.recovering_member(&mut Self::class_member));
         }
+        members
+    }
 
-        Ok(mods)
+    /// Parses a single member with the given rule. If that fails, the error
+    /// is added to the compilation unit and the member is skipped. The input
+    /// must not be at its end.
+    fn recovering_member<T, F>(&mut self, member: &mut F) -> Option<T>
+    where
+        F: FnMut(&mut Self) -> Result<T>,
+    {
+        let start = *self
+            .tokens
+            .peek()
+            .expect("a member must start with a token")
+            .span();
+        match member(self) {
+            Ok(member) => Some(member),
+            Err(e) => {
+                self.compilation_unit.add_error(Error::InvalidMember {
+                    span: start,
+                    cause: Box::new(e),
+                });
+                self.skip_member();
+                None
+            }
+        }
     }
 
-    fn package_declaration(&mut self) -> Result<QualifiedName> {
-        let package_token = self.tokens.next().unwrap(); // skip the package token
-        debug_assert!(matches!(package_token, Token::Keyword(Keyword::Package(_))));
+    fn class_member(&mut self) -> Result<ClassMember> {
+        if self.is_at_type_declaration() {
+            return Ok(ClassMember::Type(self.type_declaration()?));
+        }
 
-        self.qualified_name()
+        let doc_comment = self.doc_comment();
+        let visibility = self.visibility()?;
+        if self.is_at_field_declaration() {
+            let modifiers = self.field_modifiers()?;
+            let mut field = self.field_declaration(visibility, modifiers)?;
+            if let Some(doc_comment) = doc_comment {
+                field.set_doc_comment(doc_comment);
+            }
+            return Ok(ClassMember::Field(field));
+        }
+        let modifiers = self.method_modifiers()?;
+        let type_parameters = self.optional_type_parameters()?;
+        if self.is_at_constructor_declaration() {
+            let mut constructor =
+                self.constructor_declaration(visibility, modifiers, type_parameters)?;
+            if let Some(doc_comment) = doc_comment {
+                constructor.set_doc_comment(doc_comment);
+            }
+            return Ok(ClassMember::Constructor(constructor));
+        }
+        let mut method = self.method_declaration(visibility, modifiers, type_parameters)?;
+        if let Some(doc_comment) = doc_comment {
+            method.set_doc_comment(doc_comment);
+        }
+        Ok(ClassMember::Method(method))
     }
 
-    fn import_declaration(&mut self) -> Result<ImportDeclaration> {
-        let import_token = self.tokens.next().unwrap(); // skip the import token
-        debug_assert!(matches!(import_token, Token::Keyword(Keyword::Import(_))));
+    /// Returns whether the upcoming member is a constructor, which is the
+    /// case if a name is directly followed by `(`. A method has a return type
+    /// between its modifiers and its name.
+    fn is_at_constructor_declaration(&mut self) -> bool {
+        matches!(self.tokens.peek(), Some(Token::Ident(_)))
+            && matches!(
+                self.tokens.peek_nth(1),
+                Some(Token::Separator(Separator::LeftPar(_)))
+            )
+    }
 
-        let static_import = self
-            .tokens
-            .next_if(|t| matches!(t, Token::Keyword(Keyword::Static(_))))
-            .is_some();
+    fn interface_member(&mut self) -> Result<InterfaceMember> {
+        if self.is_at_type_declaration() {
+            return Ok(InterfaceMember::Type(self.type_declaration()?));
+        }
 
-        let name = self.qualified_name()?;
+        let doc_comment = self.doc_comment();
+        let visibility = self.visibility()?;
+        let modifiers = self.method_modifiers()?;
+        let type_parameters = self.optional_type_parameters()?;
+        let mut method = self.method_declaration(visibility, modifiers, type_parameters)?;
+        if let Some(doc_comment) = doc_comment {
+            method.set_doc_comment(doc_comment);
+        }
+        Ok(InterfaceMember::Method(method))
+    }
 
-        let last_segment_span = name
-            .segments()
-            .last()
-            .expect("qualified name must have at least one segment")
-            .span();
-        let last_segment = self
-            .parser
-            .resolve_span(*last_segment_span)
-            .expect("span of last segment must be valid");
-        let is_on_demand = last_segment == "*";
+    /// Parses a member of an annotation type, which is a constant, a nested
+    /// type or an element like `Class<?>[] types() default {};`.
+    fn annotation_member(&mut self) -> Result<AnnotationMember> {
+        if self.is_at_type_declaration() {
+            return Ok(AnnotationMember::Type(self.type_declaration()?));
+        }
 
-        Ok(match (static_import, is_on_demand) {
-            (true, true) => ImportDeclaration::StaticOnDemand(name),
-            (true, false) => ImportDeclaration::StaticSingleType(name),
-            (false, true) => ImportDeclaration::OnDemand(name),
-            (false, false) => ImportDeclaration::SingleType(name),
-        })
+        let doc_comment = self.doc_comment();
+        let visibility = self.visibility()?;
+        if self.is_at_field_declaration() {
+            let modifiers = self.field_modifiers()?;
+            let mut field = self.field_declaration(visibility, modifiers)?;
+            if let Some(doc_comment) = doc_comment {
+                field.set_doc_comment(doc_comment);
+            }
+            return Ok(AnnotationMember::Field(field));
+        }
+        let modifiers = self.method_modifiers()?;
+        let mut element = self.annotation_element(visibility, modifiers)?;
+        if let Some(doc_comment) = doc_comment {
+            element.set_doc_comment(doc_comment);
+        }
+        Ok(AnnotationMember::Element(element))
     }
 
-    fn qualified_name(&mut self) -> Result<QualifiedName> {
-        let mut qualified_name = QualifiedName::new();
+    /// Parses an element of an annotation type after its modifiers, like
+    /// `int value() default 1;`. Unlike a method, an element has a type
+    /// other than `void`, no parameters and no body.
+    fn annotation_element(
+        &mut self,
+        visibility: Visibility,
+        modifiers: MethodModifiers,
+    ) -> Result<AnnotationElement> {
+        if let Some(Token::Keyword(Keyword::Void(_))) = self.tokens.peek() {
+            return Err(Error::UnexpectedToken {
+                expected: &["type"],
+                found: self.tokens.peek().cloned(),
+            });
+        }
+        let element_type = self.r#type()?;
+        let name = self.identifier()?;
+        let mut element = AnnotationElement::new(visibility, modifiers, element_type, name);
+        self.require_token(&["("], |t| {
+            matches!(t, Token::Separator(Separator::LeftPar(_)))
+        })?;
+        self.require_token(&[")"], |t| {
+            matches!(t, Token::Separator(Separator::RightPar(_)))
+        })?;
+        for _ in 0..self.trailing_dimensions()? {
+            element.add_dimension();
+        }
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Default(_))))
+            .is_some()
+        {
+            element.set_default(self.element_value()?);
+        }
+        self.require_token(&[";"], |t| {
+            matches!(t, Token::Separator(Separator::Semicolon(_)))
+        })?;
+        Ok(element)
+    }
 
+    /// Returns whether the upcoming member is a field, which is the case if
+    /// an initializer or the terminating `;` comes before any `(`.
+    fn is_at_field_declaration(&mut self) -> bool {
+        let mut n = 0;
         loop {
-            // expect an identifier as first element
-            match self.tokens.next_if(|t| {
-                matches!(t, Token::Ident(_))
-                    || matches!(t, Token::Operator(Operator::Arithmetic(_)))
-            }) {
-                Some(Token::Ident(id)) => qualified_name.push(Identifier::from(id)),
-                Some(Token::Operator(Operator::Arithmetic(op))) => {
-                    let text = self.parser.resolve_span(op);
-                    if text == Some("*") {
-                        qualified_name.push(Identifier::from(op))
-                    } else {
-                        return Err(Error::UnexpectedToken {
-                            expected: &["*"],
-                            found: self.tokens.peek().cloned(),
-                        });
-                    }
-                }
-                _ => {
-                    return Err(Error::UnexpectedToken {
-                        expected: &["identifier"],
-                        found: self.tokens.peek().cloned(), // as opposed to the pattern we're matching, peek returns the next token, which is what we want
-                    });
-                }
+            match self.tokens.peek_nth(n).cloned() {
+                Some(Token::Separator(Separator::Semicolon(_))) => return true,
+                Some(Token::Separator(
+                    Separator::LeftPar(_) | Separator::LeftCurly(_) | Separator::RightCurly(_),
+                ))
+                | None => return false,
+                Some(token) if self.is_operator(&token, "=") => return true,
+                Some(_) => n += 1,
             }
-            // after an identifier, expect a dot and then another identifier, or break
-            match self
+        }
+    }
+
+    fn field_declaration(
+        &mut self,
+        visibility: Visibility,
+        modifiers: FieldModifiers,
+    ) -> Result<FieldDeclaration> {
+        let field_type = self.r#type()?;
+        let mut field = FieldDeclaration::new(visibility, modifiers, field_type);
+        loop {
+            let mut variable = FieldDeclarationPart::new(self.identifier()?);
+            for _ in 0..self.trailing_dimensions()? {
+                variable.add_dimension();
+            }
+            if self.next_if_operator("=").is_some() {
+                variable.set_initializer(self.expression()?);
+            }
+            field.add_variable(variable);
+
+            if self
                 .tokens
-                .next_if(|t| matches!(t, Token::Separator(Separator::Dot(_))))
+                .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                .is_none()
             {
-                Some(_) => {
-                    // dot is consumed
-                }
-                None => {
-                    // no dot, so we're done
-                    return Ok(qualified_name);
-                }
+                break;
             }
         }
+        self.require_token(&[";"], |t| {
+            matches!(t, Token::Separator(Separator::Semicolon(_)))
+        })?;
+        Ok(field)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::lexer::span::Span;
-    use crate::lexer::Lexer;
+    /// Parses the C-style array brackets after the name of a variable, like
+    /// `[][]` in `int a[][]`, and returns their number.
+    fn trailing_dimensions(&mut self) -> Result<usize> {
+        let mut dimensions = 0;
+        while self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::LeftBracket(_))))
+            .is_some()
+        {
+            self.require_token(&["]"], |t| {
+                matches!(t, Token::Separator(Separator::RightBracket(_)))
+            })?;
+            dimensions += 1;
+        }
+        Ok(dimensions)
+    }
 
-    macro_rules! apply_rule {
-        ($rule:expr, $input:expr) => {{
+    fn method_declaration(
+        &mut self,
+        visibility: Visibility,
+        modifiers: MethodModifiers,
+        type_parameters: Vec<TypeParameter>,
+    ) -> Result<MethodDeclaration> {
+        let return_type = match self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Void(_))))
+        {
+            Some(_) => None,
+            None => Some(self.r#type()?),
+        };
+        let name = self.identifier()?;
+        let mut method = MethodDeclaration::new(visibility, modifiers, return_type, name);
+        for type_parameter in type_parameters {
+            method.add_type_parameter(type_parameter);
+        }
+
+        self.expect_token(&["("], |t| {
+            matches!(t, Token::Separator(Separator::LeftPar(_)))
+        });
+        let (receiver, parameters) = self.parameters(false)?;
+        if let Some(receiver) = receiver {
+            method.set_receiver(receiver);
+        }
+        for parameter in parameters {
+            method.add_parameter(parameter);
+        }
+        self.expect_token(&[")"], |t| {
+            matches!(t, Token::Separator(Separator::RightPar(_)))
+        });
+
+        for exception in self.throws()? {
+            method.add_throws(exception);
+        }
+
+        // methods without a body, like abstract methods, end with a semicolon
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::Semicolon(_))))
+            .is_none()
+        {
+            method.set_block(self.block()?);
+        }
+
+        Ok(method)
+    }
+
+    /// Parses a constructor, whose name must directly follow the modifiers.
+    fn constructor_declaration(
+        &mut self,
+        visibility: Visibility,
+        modifiers: MethodModifiers,
+        type_parameters: Vec<TypeParameter>,
+    ) -> Result<ConstructorDeclaration> {
+        let name = self.identifier()?;
+        self.expect_token(&["("], |t| {
+            matches!(t, Token::Separator(Separator::LeftPar(_)))
+        });
+        let (receiver, parameters) = self.parameters(true)?;
+        self.expect_token(&[")"], |t| {
+            matches!(t, Token::Separator(Separator::RightPar(_)))
+        });
+        let throws = self.throws()?;
+
+        let mut constructor =
+            ConstructorDeclaration::new(visibility, modifiers, name, self.block()?);
+        for type_parameter in type_parameters {
+            constructor.add_type_parameter(type_parameter);
+        }
+        if let Some(receiver) = receiver {
+            constructor.set_receiver(receiver);
+        }
+        for parameter in parameters {
+            constructor.add_parameter(parameter);
+        }
+        for exception in throws {
+            constructor.add_throws(exception);
+        }
+        Ok(constructor)
+    }
+
+    /// Parses an optional throws clause like `throws IOException, E`.
+    fn throws(&mut self) -> Result<Vec<Type>> {
+        let mut throws = vec![];
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Throws(_))))
+            .is_some()
+        {
+            loop {
+                throws.push(self.r#type()?);
+                if self
+                    .tokens
+                    .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                    .is_none()
+                {
+                    break;
+                }
+            }
+        }
+        Ok(throws)
+    }
+
+    fn block(&mut self) -> Result<Block> {
+        self.in_scope(Self::block_statements)
+    }
+
+    /// Parses the braces and statements of a block, without opening a new
+    /// scope for its local variables.
+    fn block_statements(&mut self) -> Result<Block> {
+        let left_curly = self.require_token(&["{"], |t| {
+            matches!(t, Token::Separator(Separator::LeftCurly(_)))
+        })?;
+
+        let mut block = Block::new();
+        loop {
+            if let Some(right_curly) = self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::RightCurly(_))))
+            {
+                block.set_span(Span::new(
+                    left_curly.span().start(),
+                    right_curly.span().end(),
+                ));
+                break;
+            }
+            if self.tokens.peek().is_none() {
+                return Err(Error::UnexpectedEOF { expected: &["}"] });
+            }
+
+            match self.statement() {
+                Ok(statement) => block.add_statement(statement),
+                Err(e) => {
+                    self.compilation_unit.add_error(e);
+                    self.skip_member();
+                }
+            }
+        }
+
+        Ok(block)
+    }
+
+    /// Applies the given rule in a new scope for local variables, which ends
+    /// after the rule, even if it fails.
+    fn in_scope<T, F>(&mut self, rule: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        self.local_scopes.push(vec![]);
+        let result = rule(self);
+        self.local_scopes.pop();
+        result
+    }
+
+    /// Adds the variables of the declaration to the innermost scope, and
+    /// reports every variable whose name is already declared in this or an
+    /// enclosing scope.
+    fn declare_locals(&mut self, declaration: &LocalVariableDeclaration) {
+        for variable in declaration.variables() {
+            let span = *variable.name().span();
+            let Some(name) = self.source.resolve_span(span) else {
+                continue;
+            };
+            if self.local_scopes.iter().flatten().any(|n| *n == name) {
+                self.compilation_unit
+                    .add_error(Error::DuplicateLocalVariable { span });
+            } else if let Some(scope) = self.local_scopes.last_mut() {
+                scope.push(name);
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement> {
+        let kind = match self.tokens.peek().cloned() {
+            Some(Token::Separator(Separator::Semicolon(_))) => {
+                self.tokens.next();
+                StatementKind::Empty
+            }
+            Some(Token::Separator(Separator::LeftCurly(_))) => StatementKind::Block(self.block()?),
+            Some(Token::Keyword(Keyword::If(_))) => StatementKind::If(self.if_statement()?),
+            Some(Token::Keyword(Keyword::Switch(_))) => {
+                StatementKind::Switch(self.switch_statement()?)
+            }
+            Some(Token::Keyword(Keyword::Try(_))) => StatementKind::Try(self.try_statement()?),
+            Some(Token::Keyword(Keyword::For(_))) => self.for_statement()?,
+            Some(Token::Keyword(Keyword::Do(_))) => {
+                StatementKind::DoWhile(self.do_while_statement()?)
+            }
+            Some(Token::Keyword(Keyword::Break(_))) => {
+                self.tokens.next();
+                StatementKind::Break(BreakStatement::new(self.jump_label()?))
+            }
+            Some(Token::Keyword(Keyword::Continue(_))) => {
+                self.tokens.next();
+                StatementKind::Continue(ContinueStatement::new(self.jump_label()?))
+            }
+            Some(Token::Keyword(Keyword::Synchronized(_))) => {
+                StatementKind::Synchronized(self.synchronized_statement()?)
+            }
+            Some(Token::Keyword(Keyword::Assert(_))) => {
+                StatementKind::Assert(self.assert_statement()?)
+            }
+            _ if self.is_at_local_variable_declaration() => {
+                let declaration = self.local_variable_declaration()?;
+                self.require_token(&[";"], |t| {
+                    matches!(t, Token::Separator(Separator::Semicolon(_)))
+                })?;
+                self.declare_locals(&declaration);
+                StatementKind::LocalVariableDeclaration(declaration)
+            }
+            // TODO: other statements
+            Some(start) => {
+                let expression = self.expression()?;
+                let semicolon = self.require_token(&[";"], |t| {
+                    matches!(t, Token::Separator(Separator::Semicolon(_)))
+                })?;
+                if !is_statement_expression(&expression, self.source) {
+                    self.compilation_unit
+                        .add_error(Error::InvalidExpressionStatement {
+                            span: Span::new(start.span().start(), semicolon.span().end()),
+                        });
+                }
+                StatementKind::Expression(expression)
+            }
+            None => {
+                return Err(Error::UnexpectedEOF {
+                    expected: &["statement"],
+                })
+            }
+        };
+        Ok(Statement::new(kind))
+    }
+
+    /// Returns whether the next tokens start a local variable declaration.
+    /// A statement that starts with a name, like `Foo x;` or `Foo.bar();`, is
+    /// a declaration if the type is followed by the name of a variable.
+    fn is_at_local_variable_declaration(&mut self) -> bool {
+        if matches!(self.tokens.peek(), Some(Token::Keyword(Keyword::Final(_))))
+            || matches!(self.tokens.peek(), Some(Token::Keyword(k)) if is_primitive_type(k))
+        {
+            return true;
+        }
+        match self.peek_type_end(0) {
+            Some(n) => matches!(self.tokens.peek_nth(n), Some(Token::Ident(_))),
+            None => false,
+        }
+    }
+
+    /// Parses a local variable declaration like `final int a = 1, b`, without
+    /// the terminating semicolon.
+    fn local_variable_declaration(&mut self) -> Result<LocalVariableDeclaration> {
+        let modifiers = self.parameter_modifiers()?;
+        let ty = self.r#type()?;
+        let mut declaration = LocalVariableDeclaration::new(modifiers, ty);
+        loop {
+            let mut variable = LocalVariableDeclarationPart::new(self.identifier()?);
+            for _ in 0..self.trailing_dimensions()? {
+                variable.add_dimension();
+            }
+            if self.next_if_operator("=").is_some() {
+                variable.set_value(self.expression()?);
+            }
+            declaration.add_variable(variable);
+
+            if self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                .is_none()
+            {
+                return Ok(declaration);
+            }
+        }
+    }
+
+    fn try_statement(&mut self) -> Result<TryStatement> {
+        let try_token = self.tokens.next().unwrap(); // skip the try token
+        debug_assert!(matches!(try_token, Token::Keyword(Keyword::Try(_))));
+
+        if let Some(Token::Separator(Separator::LeftPar(span))) = self.tokens.peek() {
+            // TODO: try-with-resources
+            return Err(Error::NotImplemented(Some(*span)));
+        }
+
+        let mut statement = TryStatement::new(self.block()?);
+        while self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Catch(_))))
+            .is_some()
+        {
+            self.require_token(&["("], |t| {
+                matches!(t, Token::Separator(Separator::LeftPar(_)))
+            })?;
+            let modifiers = self.parameter_modifiers()?;
+            let mut types = vec![self.r#type()?];
+            while self.next_if_operator("|").is_some() {
+                types.push(self.r#type()?);
+            }
+            let name = self.identifier()?;
+            self.require_token(&[")"], |t| {
+                matches!(t, Token::Separator(Separator::RightPar(_)))
+            })?;
+
+            let parameter = CatchParameter::new(modifiers, types, name);
+            statement.add_catch(CatchClause::new(parameter, self.block()?));
+        }
+
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Finally(_))))
+            .is_some()
+        {
+            statement.set_finally(self.block()?);
+        } else if statement.catches().is_empty() {
+            return Err(Error::UnexpectedToken {
+                expected: &["catch", "finally"],
+                found: self.tokens.peek().cloned(),
+            });
+        }
+        Ok(statement)
+    }
+
+    /// Parses a basic for statement or an enhanced for statement, like
+    /// `for (String s : list)`.
+    fn for_statement(&mut self) -> Result<StatementKind> {
+        let for_token = self.tokens.next().unwrap(); // skip the for token
+        debug_assert!(matches!(for_token, Token::Keyword(Keyword::For(_))));
+
+        self.require_token(&["("], |t| {
+            matches!(t, Token::Separator(Separator::LeftPar(_)))
+        })?;
+
+        let mut initializers = vec![];
+        if self.is_at_local_variable_declaration() {
+            let declaration = self.local_variable_declaration()?;
+            if self.next_if_operator(":").is_some() {
+                let variable = match declaration.variables() {
+                    [variable] if variable.value().is_none() => variable.name().clone(),
+                    _ => {
+                        return Err(Error::UnexpectedToken {
+                            expected: &[";"],
+                            found: self.tokens.peek().cloned(),
+                        })
+                    }
+                };
+                let variable = ForEachVariableDeclaration::new(
+                    declaration.modifiers().clone(),
+                    declaration.ty().clone(),
+                    variable,
+                );
+                let expression = self.expression()?;
+                self.require_token(&[")"], |t| {
+                    matches!(t, Token::Separator(Separator::RightPar(_)))
+                })?;
+                let block = self.loop_body()?;
+                return Ok(StatementKind::ForEach(ForEachStatement::new(
+                    variable, expression, block,
+                )));
+            }
+            initializers.push(ForInitializer::LocalVariableDeclaration(declaration));
+        } else {
+            initializers = self
+                .expressions_until(|t| matches!(t, Token::Separator(Separator::Semicolon(_))))?
+                .into_iter()
+                .map(ForInitializer::Expression)
+                .collect();
+        }
+        self.require_token(&[";"], |t| {
+            matches!(t, Token::Separator(Separator::Semicolon(_)))
+        })?;
+
+        let condition = match self.tokens.peek() {
+            Some(Token::Separator(Separator::Semicolon(_))) => None,
+            _ => Some(self.expression()?),
+        };
+        self.require_token(&[";"], |t| {
+            matches!(t, Token::Separator(Separator::Semicolon(_)))
+        })?;
+
+        let updaters =
+            self.expressions_until(|t| matches!(t, Token::Separator(Separator::RightPar(_))))?;
+        self.require_token(&[")"], |t| {
+            matches!(t, Token::Separator(Separator::RightPar(_)))
+        })?;
+
+        let block = self.loop_body()?;
+        Ok(StatementKind::For(ForStatement::new(
+            initializers,
+            condition,
+            updaters,
+            block,
+        )))
+    }
+
+    /// Parses a possibly empty, comma-separated list of expressions, until
+    /// the given token, which is not consumed.
+    fn expressions_until<F>(&mut self, end: F) -> Result<Vec<Expression>>
+    where
+        F: Fn(&Token) -> bool,
+    {
+        let mut expressions = vec![];
+        if self.tokens.peek().is_some_and(&end) {
+            return Ok(expressions);
+        }
+        loop {
+            expressions.push(self.expression()?);
+            if self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                .is_none()
+            {
+                return Ok(expressions);
+            }
+        }
+    }
+
+    /// Parses the rest of an array creation after its type, which is either
+    /// a list of dimension expressions like `[3][]`, or an initializer if
+    /// the type already ends in empty brackets, like in `new int[] { 1 }`.
+    fn array_creation(&mut self, ty: Type) -> Result<ArrayCreation> {
+        let initializer_required = ty.is_array();
+        let mut creation = ArrayCreation::new(ty);
+        if initializer_required {
+            creation.set_initializer(self.array_initializer()?);
+            return Ok(creation);
+        }
+
+        while self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::LeftBracket(_))))
+            .is_some()
+        {
+            if self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::RightBracket(_))))
+                .is_some()
+            {
+                creation.add_extra_dimension();
+                continue;
+            }
+            if creation.extra_dimensions() > 0 {
+                // dimension expressions can't follow empty brackets
+                return Err(Error::UnexpectedToken {
+                    expected: &["]"],
+                    found: self.tokens.peek().cloned(),
+                });
+            }
+            creation.add_dimension(self.expression()?);
+            self.require_token(&["]"], |t| {
+                matches!(t, Token::Separator(Separator::RightBracket(_)))
+            })?;
+        }
+        if let Some(Token::Separator(Separator::LeftCurly(span))) = self.tokens.peek() {
+            return Err(Error::ArrayInitializerWithDimensions { span: *span });
+        }
+        Ok(creation)
+    }
+
+    /// Parses an array initializer like `{ 1, 2, }`, where nested braces
+    /// are parsed as nested initializers.
+    fn array_initializer(&mut self) -> Result<ArrayInitializer> {
+        let left_curly = self.require_token(&["{"], |t| {
+            matches!(t, Token::Separator(Separator::LeftCurly(_)))
+        })?;
+        let mut elements = vec![];
+        loop {
+            if let Some(right_curly) = self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::RightCurly(_))))
+            {
+                let span = Span::new(left_curly.span().start(), right_curly.span().end());
+                return Ok(ArrayInitializer::new(elements, span));
+            }
+
+            if matches!(
+                self.tokens.peek(),
+                Some(Token::Separator(Separator::LeftCurly(_)))
+            ) {
+                elements.push(ArrayElement::Initializer(self.array_initializer()?));
+            } else {
+                elements.push(ArrayElement::Expression(self.expression()?));
+            }
+
+            // a trailing comma before the closing brace is allowed
+            if self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                .is_none()
+            {
+                let right_curly = self.require_token(&[",", "}"], |t| {
+                    matches!(t, Token::Separator(Separator::RightCurly(_)))
+                })?;
+                let span = Span::new(left_curly.span().start(), right_curly.span().end());
+                return Ok(ArrayInitializer::new(elements, span));
+            }
+        }
+    }
+
+    /// Parses the body of a loop or an if statement branch, where a single
+    /// statement is represented as a block that only contains that statement.
+    fn loop_body(&mut self) -> Result<Block> {
+        if matches!(
+            self.tokens.peek(),
+            Some(Token::Separator(Separator::LeftCurly(_)))
+        ) {
+            return self.block();
+        }
+
+        let mut block = Block::new();
+        block.add_statement(self.statement()?);
+        Ok(block)
+    }
+
+    /// Parses an if statement. A chain of `else if` branches is parsed
+    /// iteratively into [`IfStatement::else_ifs`], so that long chains don't
+    /// recurse.
+    fn if_statement(&mut self) -> Result<IfStatement> {
+        let if_token = self.tokens.next().unwrap(); // skip the if token
+        debug_assert!(matches!(if_token, Token::Keyword(Keyword::If(_))));
+
+        let condition = self.parenthesized_condition()?;
+        let mut statement = IfStatement::new(condition, self.loop_body()?);
+        while self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Else(_))))
+            .is_some()
+        {
+            if self
+                .tokens
+                .next_if(|t| matches!(t, Token::Keyword(Keyword::If(_))))
+                .is_none()
+            {
+                statement.set_else_statement(self.loop_body()?);
+                break;
+            }
+            let condition = self.parenthesized_condition()?;
+            statement.add_else_if(ElseIfStatement::new(condition, self.loop_body()?));
+        }
+        Ok(statement)
+    }
+
+    /// Parses a statement like `do { ... } while (condition);`, where the
+    /// parentheses and the semicolon are required.
+    fn do_while_statement(&mut self) -> Result<DoWhileStatement> {
+        let do_token = self.tokens.next().unwrap(); // skip the do token
+        debug_assert!(matches!(do_token, Token::Keyword(Keyword::Do(_))));
+
+        let block = self.loop_body()?;
+        self.require_token(&["while"], |t| {
+            matches!(t, Token::Keyword(Keyword::While(_)))
+        })?;
+        let condition = self.parenthesized_condition()?;
+        self.require_token(&[";"], |t| {
+            matches!(t, Token::Separator(Separator::Semicolon(_)))
+        })?;
+        Ok(DoWhileStatement::new(block, condition))
+    }
+
+    /// Parses the optional label and the required semicolon after `break` or
+    /// `continue`, which must already be consumed.
+    fn jump_label(&mut self) -> Result<Option<Identifier>> {
+        let label = match self.tokens.peek() {
+            Some(Token::Ident(_)) => Some(self.identifier()?),
+            _ => None,
+        };
+        self.require_token(&[";"], |t| {
+            matches!(t, Token::Separator(Separator::Semicolon(_)))
+        })?;
+        Ok(label)
+    }
+
+    /// Parses a condition in parentheses, like `(a < b)` in `if (a < b)`.
+    fn parenthesized_condition(&mut self) -> Result<Expression> {
+        self.require_token(&["("], |t| {
+            matches!(t, Token::Separator(Separator::LeftPar(_)))
+        })?;
+        let condition = self.expression()?;
+        self.require_token(&[")"], |t| {
+            matches!(t, Token::Separator(Separator::RightPar(_)))
+        })?;
+        Ok(condition)
+    }
+
+    fn synchronized_statement(&mut self) -> Result<SynchronizedStatement> {
+        let synchronized_token = self.tokens.next().unwrap(); // skip the synchronized token
+        debug_assert!(matches!(
+            synchronized_token,
+            Token::Keyword(Keyword::Synchronized(_))
+        ));
+
+        self.require_token(&["("], |t| {
+            matches!(t, Token::Separator(Separator::LeftPar(_)))
+        })?;
+        let expression = self.expression()?;
+        self.require_token(&[")"], |t| {
+            matches!(t, Token::Separator(Separator::RightPar(_)))
+        })?;
+        Ok(SynchronizedStatement::new(expression, self.block()?))
+    }
+
+    fn assert_statement(&mut self) -> Result<AssertStatement> {
+        let assert_token = self.tokens.next().unwrap(); // skip the assert token
+        debug_assert!(matches!(assert_token, Token::Keyword(Keyword::Assert(_))));
+
+        let condition = self.expression()?;
+        let detail = match self.next_if_operator(":") {
+            Some(_) => Some(self.expression()?),
+            None => None,
+        };
+        self.require_token(&[";"], |t| {
+            matches!(t, Token::Separator(Separator::Semicolon(_)))
+        })?;
+        Ok(AssertStatement::new(condition, detail))
+    }
+
+    fn switch_statement(&mut self) -> Result<SwitchStatement> {
+        let switch_token = self.tokens.next().unwrap(); // skip the switch token
+        debug_assert!(matches!(switch_token, Token::Keyword(Keyword::Switch(_))));
+
+        self.require_token(&["("], |t| {
+            matches!(t, Token::Separator(Separator::LeftPar(_)))
+        })?;
+        let mut switch = SwitchStatement::new(self.expression()?);
+        self.require_token(&[")"], |t| {
+            matches!(t, Token::Separator(Separator::RightPar(_)))
+        })?;
+
+        self.require_token(&["{"], |t| {
+            matches!(t, Token::Separator(Separator::LeftCurly(_)))
+        })?;
+        // the cases share the scope of the switch block
+        self.in_scope(|ctx| {
+            while ctx
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::RightCurly(_))))
+                .is_none()
+            {
+                switch.add_case(ctx.switch_case()?);
+            }
+            Ok(switch)
+        })
+    }
+
+    fn switch_case(&mut self) -> Result<SwitchCase> {
+        let labels = match self.tokens.next() {
+            Some(Token::Keyword(Keyword::Default(_))) => vec![CaseLabel::Default],
+            Some(Token::Keyword(Keyword::Case(_))) => {
+                let mut labels = vec![self.case_label()?];
+                while self
+                    .tokens
+                    .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                    .is_some()
+                {
+                    labels.push(self.case_label()?);
+                }
+                labels
+            }
+            found => {
+                return Err(Error::UnexpectedToken {
+                    expected: &["case", "default", "}"],
+                    found,
+                })
+            }
+        };
+
+        if self.next_if_operator("->").is_some() {
+            let mut case = SwitchCase::new(labels, true);
+            case.add_statement(self.statement()?);
+            return Ok(case);
+        }
+
+        self.require_operator(&[":"])?;
+        let mut case = SwitchCase::new(labels, false);
+        while !matches!(
+            self.tokens.peek(),
+            Some(Token::Keyword(Keyword::Case(_) | Keyword::Default(_)))
+                | Some(Token::Separator(Separator::RightCurly(_)))
+                | None
+        ) {
+            case.add_statement(self.statement()?);
+        }
+        Ok(case)
+    }
+
+    fn case_label(&mut self) -> Result<CaseLabel> {
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Default(_))))
+            .is_some()
+        {
+            return Ok(CaseLabel::Default);
+        }
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Literal(Literal::Null(_))))
+            .is_some()
+        {
+            return Ok(CaseLabel::Null);
+        }
+
+        if self.is_at_type_pattern() {
+            let ty = self.r#type()?;
+            let name = self.identifier()?;
+            return Ok(CaseLabel::TypePattern(ty, name));
+        }
+        Ok(CaseLabel::Expression(self.conditional()?))
+    }
+
+    /// Returns whether the next tokens are a type followed by a name, like `String s`.
+    fn is_at_type_pattern(&mut self) -> bool {
+        let end = match self.tokens.peek() {
+            Some(Token::Keyword(k)) if is_primitive_type(k) => Some(1),
+            _ => self.peek_type_end(0),
+        };
+        end.is_some_and(|n| matches!(self.tokens.peek_nth(n), Some(Token::Ident(_))))
+    }
+
+    fn enum_member(&mut self) -> Result<EnumMember> {
+        Ok(match self.class_member()? {
+            ClassMember::Type(v) => EnumMember::Type(v),
+            ClassMember::Field(v) => EnumMember::Field(v),
+            ClassMember::Method(v) => EnumMember::Method(v),
+            ClassMember::Constructor(v) => EnumMember::Constructor(v),
+        })
+    }
+
+    /// Returns whether the next tokens start a class, interface or enum declaration,
+    /// possibly preceded by visibility and class modifiers.
+    fn is_at_type_declaration(&mut self) -> bool {
+        let mut n = 0;
+        while matches!(
+            self.tokens.peek_nth(n),
+            Some(Token::Keyword(
+                Keyword::Public(_)
+                    | Keyword::Protected(_)
+                    | Keyword::Private(_)
+                    | Keyword::Abstract(_)
+                    | Keyword::Final(_)
+                    | Keyword::Static(_)
+            ))
+        ) {
+            n += 1;
+        }
+        match self.tokens.peek_nth(n) {
+            Some(Token::Keyword(Keyword::Class(_) | Keyword::Interface(_) | Keyword::Enum(_))) => {
+                true
+            }
+            Some(Token::Separator(Separator::At(_))) => matches!(
+                self.tokens.peek_nth(n + 1),
+                Some(Token::Keyword(Keyword::Interface(_)))
+            ),
+            _ => false,
+        }
+    }
+
+    /// Skips tokens until the end of the current member or statement, which is
+    /// either a `;` or a balanced `{ ... }` block, whichever comes first. This
+    /// is used to recover after a member or statement could not be parsed.
+    ///
+    /// A `}` that would close the enclosing body or block is not consumed.
+    fn skip_member(&mut self) {
+        let mut depth = 0_usize;
+        while let Some(token) = self
+            .tokens
+            .next_if(|t| depth > 0 || !matches!(t, Token::Separator(Separator::RightCurly(_))))
+        {
+            match token {
+                Token::Separator(Separator::LeftCurly(_)) => depth += 1,
+                Token::Separator(Separator::RightCurly(_)) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                Token::Separator(Separator::Semicolon(_)) if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression> {
+        self.assignment()
+    }
+
+    /// Parses an assignment, which is right associative, so `a = b = c`
+    /// assigns `c` to `b` and the result of that to `a`.
+    fn assignment(&mut self) -> Result<Expression> {
+        let target = self.conditional()?;
+        match self
+            .tokens
+            .next_if(|t| matches!(t, Token::Operator(Operator::Assignment(_))))
+        {
+            Some(operator) => {
+                let value = self.assignment()?;
+                Ok(Expression::Assignment(Assignment::new(
+                    target,
+                    *operator.span(),
+                    value,
+                )))
+            }
+            None => Ok(target),
+        }
+    }
+
+    fn conditional(&mut self) -> Result<Expression> {
+        let condition = self.binary(0)?;
+        if self.next_if_operator("?").is_none() {
+            return Ok(condition);
+        }
+
+        let then = self.expression()?;
+        self.require_operator(&[":"])?;
+        let otherwise = self.conditional()?;
+        Ok(Expression::Conditional(Conditional::new(
+            condition, then, otherwise,
+        )))
+    }
+
+    /// Parses binary operations by precedence climbing. Only operators with
+    /// at least the given precedence are consumed, and operators of the same
+    /// precedence are left associative.
+    ///
+    /// `instanceof` is parsed as a binary operator as well, but its right
+    /// operand is a type instead of an expression.
+    fn binary(&mut self, min_precedence: u8) -> Result<Expression> {
+        let mut left = self.unary()?;
+        while let Some((operator, precedence)) = self.peek_binary_operator() {
+            if precedence < min_precedence {
+                break;
+            }
+            if let Some(Token::Keyword(Keyword::InstanceOf(_))) = self.tokens.next() {
+                left = Expression::InstanceOf(InstanceOf::new(left, self.r#type()?));
+                continue;
+            }
+            let right = self.binary(precedence + 1)?;
+            left = Expression::Binary(Binary::new(left, operator, right));
+        }
+        Ok(left)
+    }
+
+    /// Returns the span and precedence of the next token, if it is a binary
+    /// operator or `instanceof`.
+    fn peek_binary_operator(&mut self) -> Option<(Span, u8)> {
+        let span = match self.tokens.peek() {
+            Some(Token::Operator(operator)) => *operator.span(),
+            Some(Token::Keyword(Keyword::InstanceOf(span))) => *span,
+            _ => return None,
+        };
+        let precedence = binary_precedence(self.source.resolve_span(span)?)?;
+        Some((span, precedence))
+    }
+
+    fn unary(&mut self) -> Result<Expression> {
+        if let Some(operator) = self.next_prefix_operator() {
+            let operand = match self.tokens.peek() {
+                // the minimum values of `int` and `long` can only be written
+                // as a negated literal, like `-2147483648`
+                Some(Token::Literal(Literal::Integer(span)))
+                    if self.source.resolve_span(operator) == Some("-") =>
+                {
+                    let span = *span;
+                    self.tokens.next();
+                    Expression::IntegerLiteral(self.integer_literal(span, true))
+                }
+                _ => self.unary()?,
+            };
+            return Ok(Expression::Unary(Unary::new(operator, operand, false)));
+        }
+
+        if self.is_at_cast() {
+            self.tokens.next(); // skip the opening parenthesis
+            let ty = self.r#type()?;
+            self.require_token(&[")"], |t| {
+                matches!(t, Token::Separator(Separator::RightPar(_)))
+            })?;
+            let expression = self.unary()?;
+            return Ok(Expression::Cast(Cast::new(ty, expression)));
+        }
+
+        let primary = self.primary()?;
+        self.postfix(primary)
+    }
+
+    /// Consumes the next token if it is a prefix operator like `-`, `!` or
+    /// `++`, and returns its span.
+    fn next_prefix_operator(&mut self) -> Option<Span> {
+        ["++", "--", "+", "-", "!", "~"]
+            .into_iter()
+            .find_map(|operator| self.next_if_operator(operator))
+            .map(|operator| *operator.span())
+    }
+
+    /// Scans the tokens of a (non-primitive) type starting at the `n`-th next token,
+    /// without consuming them. Returns the index of the first token after the
+    /// type, or `None` if the tokens can't be a type.
+    fn peek_type_end(&mut self, mut n: usize) -> Option<usize> {
+        // the name of the type
+        loop {
+            if !matches!(self.tokens.peek_nth(n), Some(Token::Ident(_))) {
+                return None;
+            }
+            n += 1;
+            if !matches!(
+                self.tokens.peek_nth(n),
+                Some(Token::Separator(Separator::Dot(_)))
+            ) {
+                break;
+            }
+            n += 1;
+        }
+
+        // type arguments, which may only consist of tokens that can appear in types
+        if self.peek_nth_is_operator(n, "<") {
+            let mut depth = 0_usize;
+            loop {
+                match self.tokens.peek_nth(n).cloned() {
+                    Some(token) if self.is_operator(&token, "<") => depth += 1,
+                    Some(token) if self.is_operator(&token, ">") => depth -= 1,
+                    // closes multiple scopes, like in `Map<K, List<V>>`
+                    Some(token) if self.is_operator(&token, ">>") => {
+                        depth = depth.checked_sub(2)?
+                    }
+                    Some(token) if self.is_operator(&token, ">>>") => {
+                        depth = depth.checked_sub(3)?
+                    }
+                    Some(Token::Ident(_))
+                    | Some(Token::Separator(
+                        Separator::Dot(_)
+                        | Separator::Comma(_)
+                        | Separator::LeftBracket(_)
+                        | Separator::RightBracket(_),
+                    ))
+                    | Some(Token::Operator(Operator::QuestionMark(_)))
+                    | Some(Token::Keyword(Keyword::Extends(_) | Keyword::Super(_))) => {}
+                    Some(Token::Keyword(k)) if is_primitive_type(&k) => {}
+                    _ => return None,
+                }
+                n += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+
+        // array dimensions
+        while matches!(
+            self.tokens.peek_nth(n),
+            Some(Token::Separator(Separator::LeftBracket(_)))
+        ) && matches!(
+            self.tokens.peek_nth(n + 1),
+            Some(Token::Separator(Separator::RightBracket(_)))
+        ) {
+            n += 2;
+        }
+        Some(n)
+    }
+
+    /// Returns whether the next tokens start a cast like `(String) x` or
+    /// `(List<String>) x`, as opposed to a parenthesized expression like
+    /// `(x)`. No tokens are consumed.
+    fn is_at_cast(&mut self) -> bool {
+        if !matches!(
+            self.tokens.peek(),
+            Some(Token::Separator(Separator::LeftPar(_)))
+        ) {
+            return false;
+        }
+        // a primitive type can't be the start of an expression
+        if matches!(self.tokens.peek_nth(1), Some(Token::Keyword(k)) if is_primitive_type(k)) {
+            return true;
+        }
+
+        let Some(n) = self.peek_type_end(1) else {
+            return false;
+        };
+
+        if !matches!(
+            self.tokens.peek_nth(n),
+            Some(Token::Separator(Separator::RightPar(_)))
+        ) {
+            return false;
+        }
+
+        // a parenthesized type is only a cast if an operand follows
+        matches!(
+            self.tokens.peek_nth(n + 1),
+            Some(
+                Token::Ident(_)
+                    | Token::Literal(_)
+                    | Token::Separator(Separator::LeftPar(_))
+                    | Token::Keyword(Keyword::New(_) | Keyword::This(_) | Keyword::Super(_))
+            )
+        ) || self.peek_nth_is_operator(n + 1, "!")
+            || self.peek_nth_is_operator(n + 1, "~")
+    }
+
+    fn primary(&mut self) -> Result<Expression> {
+        match self.tokens.peek().cloned() {
+            Some(Token::Literal(Literal::String(span))) => {
+                self.tokens.next();
+                Ok(Expression::StringLiteral(StringLiteral::new(span)))
+            }
+            Some(Token::Literal(Literal::Boolean(span))) => {
+                self.tokens.next();
+                Ok(Expression::BooleanLiteral(BooleanLiteral::new(span)))
+            }
+            Some(Token::Literal(Literal::Null(span))) => {
+                self.tokens.next();
+                Ok(Expression::NullLiteral(NullLiteral::new(span)))
+            }
+            Some(Token::Literal(Literal::Integer(span))) => {
+                self.tokens.next();
+                Ok(Expression::IntegerLiteral(
+                    self.integer_literal(span, false),
+                ))
+            }
+            Some(Token::Separator(Separator::LeftPar(_))) => {
+                self.tokens.next();
+                let expression = self.expression()?;
+                self.require_token(&[")"], |t| {
+                    matches!(t, Token::Separator(Separator::RightPar(_)))
+                })?;
+                Ok(Expression::Parenthesized(Box::new(expression)))
+            }
+            Some(Token::Keyword(Keyword::New(_))) => {
+                self.tokens.next();
+                let ty = self.r#type()?;
+                if ty.is_array()
+                    || matches!(
+                        self.tokens.peek(),
+                        Some(Token::Separator(Separator::LeftBracket(_)))
+                    )
+                {
+                    return Ok(Expression::ArrayCreation(self.array_creation(ty)?));
+                }
+                let arguments = self.arguments()?;
+                let mut creation = ObjectCreation::new(ty, arguments);
+                if self
+                    .tokens
+                    .next_if(|t| matches!(t, Token::Separator(Separator::LeftCurly(_))))
+                    .is_some()
+                {
+                    // the members may declare locals with the names of
+                    // enclosing locals
+                    let local_scopes = std::mem::take(&mut self.local_scopes);
+                    let mut body = AnonymousClassBody::new();
+                    for member in self.members(Self::class_member) {
+                        body.add_member(member);
+                    }
+                    creation.set_body(body);
+                    self.local_scopes = local_scopes;
+                }
+                Ok(Expression::ObjectCreation(creation))
+            }
+            Some(Token::Keyword(Keyword::This(span))) => {
+                self.tokens.next();
+                Ok(Expression::This(This::new(None, span)))
+            }
+            Some(Token::Ident(_)) => {
+                let name = self.expression_name()?;
+                if matches!(
+                    self.tokens.peek(),
+                    Some(Token::Separator(Separator::Dot(_)))
+                ) && matches!(
+                    self.tokens.peek_nth(1),
+                    Some(Token::Keyword(Keyword::This(_)))
+                ) {
+                    self.tokens.next();
+                    let this = self.tokens.next().expect("this keyword was peeked");
+                    let start = name.span().expect("expression name must not be empty");
+                    let span = Span::new(start.start(), this.span().end());
+                    return Ok(Expression::This(This::new(Some(name), span)));
+                }
+                if matches!(
+                    self.tokens.peek(),
+                    Some(Token::Separator(Separator::LeftPar(_)))
+                ) {
+                    let arguments = self.arguments()?;
+                    Ok(Expression::MethodCall(MethodCall::new(
+                        None, name, arguments,
+                    )))
+                } else {
+                    Ok(Expression::Name(name))
+                }
+            }
+            found => Err(Error::UnexpectedToken {
+                expected: &["expression"],
+                found,
+            }),
+        }
+    }
+
+    /// Creates the integer literal of an already consumed token, and reports
+    /// it if it is out of range. `negated` tells whether the literal is the
+    /// direct operand of a unary minus.
+    fn integer_literal(&mut self, span: Span, negated: bool) -> IntegerLiteral {
+        let text = self
+            .source
+            .resolve_span(span)
+            .expect("span of integer literal must be valid");
+        let radix = Radix::of(text);
+        let long = text.ends_with(['l', 'L']);
+        if self.literal_overflow == LiteralOverflowPolicy::Error
+            && !is_integer_literal_in_range(text, radix, long, negated)
+        {
+            self.compilation_unit
+                .add_error(Error::IntegerLiteralOutOfRange { span });
+        }
+        IntegerLiteral::new(span, radix, long)
+    }
+
+    /// Parses a dot-separated name in an expression, like `System.out`.
+    fn expression_name(&mut self) -> Result<QualifiedName> {
+        let mut name = QualifiedName::new();
+        name.push(self.identifier()?);
+        // a dot that is not followed by a name, like in `Outer.this`, is
+        // not part of the name
+        while matches!(
+            self.tokens.peek(),
+            Some(Token::Separator(Separator::Dot(_)))
+        ) && matches!(self.tokens.peek_nth(1), Some(Token::Ident(_)))
+        {
+            self.tokens.next();
+            name.push(self.identifier()?);
+        }
+        Ok(name)
+    }
+
+    /// Applies postfix field accesses, method calls, array accesses, increments
+    /// and decrements to the given expression, like `.length()` in `"hi".length()`.
+    fn postfix(&mut self, mut expression: Expression) -> Result<Expression> {
+        loop {
+            expression = match self.tokens.peek() {
+                Some(Token::Separator(Separator::Dot(_))) => {
+                    self.tokens.next();
+                    let name = self.identifier()?;
+                    if matches!(
+                        self.tokens.peek(),
+                        Some(Token::Separator(Separator::LeftPar(_)))
+                    ) {
+                        let arguments = self.arguments()?;
+                        Expression::MethodCall(MethodCall::new(
+                            Some(expression),
+                            QualifiedName::from(vec![name]),
+                            arguments,
+                        ))
+                    } else {
+                        Expression::FieldAccess(FieldAccess::new(expression, name))
+                    }
+                }
+                Some(Token::Separator(Separator::LeftBracket(_))) => {
+                    self.tokens.next();
+                    let index = self.expression()?;
+                    self.require_token(&["]"], |t| {
+                        matches!(t, Token::Separator(Separator::RightBracket(_)))
+                    })?;
+                    Expression::ArrayAccess(ArrayAccess::new(expression, index))
+                }
+                Some(Token::Operator(Operator::Unary(operator))) => {
+                    let operator = *operator;
+                    self.tokens.next();
+                    Expression::Unary(Unary::new(operator, expression, true))
+                }
+                _ => return Ok(expression),
+            };
+        }
+    }
+
+    /// Parses a parenthesized, comma-separated list of arguments.
+    fn arguments(&mut self) -> Result<Vec<Expression>> {
+        self.require_token(&["("], |t| {
+            matches!(t, Token::Separator(Separator::LeftPar(_)))
+        })?;
+
+        let mut arguments = vec![];
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::RightPar(_))))
+            .is_some()
+        {
+            return Ok(arguments);
+        }
+        loop {
+            arguments.push(self.expression()?);
+            match self.require_token(&[",", ")"], |t| {
+                matches!(
+                    t,
+                    Token::Separator(Separator::Comma(_) | Separator::RightPar(_))
+                )
+            })? {
+                Token::Separator(Separator::Comma(_)) => continue,
+                _ => return Ok(arguments),
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Result<Identifier> {
+        match self.tokens.next_if(|t| matches!(t, Token::Ident(_))) {
+            Some(Token::Ident(id)) => Ok(Identifier::from(id)),
+            _ => match self.tokens.peek().cloned() {
+                Some(Token::Keyword(keyword)) if is_reserved_keyword(&keyword) => {
+                    Err(Error::ReservedKeyword { keyword })
+                }
+                Some(Token::Keyword(keyword)) => Err(Error::KeywordAsIdentifier { keyword }),
+                found => Err(Error::UnexpectedToken {
+                    expected: &["identifier"],
+                    found,
+                }),
+            },
+        }
+    }
+
+    /// Parses a type, which is either a primitive type or a qualified name,
+    /// optionally followed by type arguments.
+    ///
+    /// Primitive types are represented as a qualified name with a single
+    /// segment, which is the keyword.
+    fn r#type(&mut self) -> Result<Type> {
+        if let Some(Token::Keyword(keyword)) = self.tokens.peek() {
+            if is_reserved_keyword(keyword) {
+                return Err(Error::ReservedKeyword { keyword: *keyword });
+            }
+        }
+        if let Some(Token::Operator(Operator::QuestionMark(span))) = self.tokens.peek() {
+            return Err(Error::MisplacedWildcard { span: *span });
+        }
+
+        let mut ty = match self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(k) if is_primitive_type(k)))
+        {
+            Some(Token::Keyword(keyword)) => Type::primitive(
+                primitive_kind(&keyword).expect("keyword must be a primitive type"),
+                *keyword.span(),
+            ),
+            _ => {
+                let name = self.qualified_name()?;
+                let start = name
+                    .span()
+                    .expect("qualified name must have at least one segment");
+                match self.next_if_operator("<") {
+                    Some(_) => {
+                        let type_arguments = self.type_arguments()?;
+                        let end = self.require_closing_angle_bracket()?;
+                        let span = Span::new(start.start(), end.span().end());
+                        Type::new(name, type_arguments, span)
+                    }
+                    None => Type::from(name),
+                }
+            }
+        };
+
+        // array dimensions, but not an array creation like `new int[5]`
+        while matches!(
+            self.tokens.peek_nth(1),
+            Some(Token::Separator(Separator::RightBracket(_)))
+        ) {
+            match self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::LeftBracket(_))))
+            {
+                Some(_) => {
+                    let right_bracket = self.tokens.next().expect("right bracket was peeked");
+                    ty.add_dimension(right_bracket.span().end());
+                }
+                None => break,
+            }
+        }
+        Ok(ty)
+    }
+
+    /// Parses the comma-separated type arguments of a generic type. The opening
+    /// `<` must already be consumed, the closing `>` is not consumed.
+    fn type_arguments(&mut self) -> Result<Vec<TypeArgument>> {
+        let mut type_arguments = vec![self.type_argument()?];
+        while self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+            .is_some()
+        {
+            type_arguments.push(self.type_argument()?);
+        }
+        Ok(type_arguments)
+    }
+
+    /// Parses a single type argument, which may be a wildcard. A primitive
+    /// type like the `int` in `List<int>` is reported as an error, but still
+    /// parsed as a type. Arrays of primitive types, like `int[]`, are
+    /// reference types and thus valid.
+    fn type_argument(&mut self) -> Result<TypeArgument> {
+        let keyword = match self.tokens.peek() {
+            Some(Token::Operator(Operator::QuestionMark(span))) => {
+                let span = *span;
+                self.tokens.next();
+                return Ok(TypeArgument::Wildcard(self.wildcard(span)?));
+            }
+            Some(Token::Keyword(keyword)) => Some(*keyword),
+            _ => None,
+        };
+        let ty = self.r#type()?;
+        if let Some(keyword) = keyword {
+            if ty.primitive_kind().is_some() && ty.dimensions() == 0 {
+                self.compilation_unit
+                    .add_error(Error::PrimitiveTypeArgument { keyword });
+            }
+        }
+        Ok(TypeArgument::Type(ty))
+    }
+
+    /// Parses the optional bound of a wildcard type argument. The `?` at the
+    /// given span must already be consumed.
+    fn wildcard(&mut self, question_mark: Span) -> Result<Wildcard> {
+        let bound = match self.tokens.next_if(|t| {
+            matches!(
+                t,
+                Token::Keyword(Keyword::Extends(_)) | Token::Keyword(Keyword::Super(_))
+            )
+        }) {
+            Some(Token::Keyword(Keyword::Extends(_))) => {
+                Some(WildcardBound::Extends(self.r#type()?))
+            }
+            Some(_) => Some(WildcardBound::Super(self.r#type()?)),
+            None => None,
+        };
+        // unlike type parameters, wildcards can't have an intersection bound
+        if bound.is_some() {
+            if let Some(ampersand) = self.next_if_operator("&") {
+                return Err(Error::WildcardIntersectionBound {
+                    span: *ampersand.span(),
+                });
+            }
+        }
+        let end = match &bound {
+            Some(WildcardBound::Extends(ty) | WildcardBound::Super(ty)) => {
+                ty.span().expect("type must have a span").end()
+            }
+            None => question_mark.end(),
+        };
+        Ok(Wildcard::new(bound, Span::new(question_mark.start(), end)))
+    }
+
+    /// Parses the type parameters of a generic declaration including the
+    /// surrounding `<` and `>`, if the next token is a `<`.
+    fn optional_type_parameters(&mut self) -> Result<Vec<TypeParameter>> {
+        match self.next_if_operator("<") {
+            Some(_) => self.type_parameters(),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Parses the comma-separated type parameters of a generic declaration,
+    /// including the closing `>`. The opening `<` must already be consumed.
+    fn type_parameters(&mut self) -> Result<Vec<TypeParameter>> {
+        let mut type_parameters = vec![self.type_parameter()?];
+        while self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+            .is_some()
+        {
+            type_parameters.push(self.type_parameter()?);
+        }
+        self.require_closing_angle_bracket()?;
+        Ok(type_parameters)
+    }
+
+    fn type_parameter(&mut self) -> Result<TypeParameter> {
+        let name = self.identifier()?;
+        let mut bounds = vec![];
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Extends(_))))
+            .is_some()
+        {
+            bounds.push(self.r#type()?);
+            while self.next_if_operator("&").is_some() {
+                bounds.push(self.r#type()?);
+            }
+        }
+        Ok(TypeParameter::new(name, bounds))
+    }
+
+    /// Parses the visibility modifiers of a declaration. At most one of
+    /// `public`, `protected` and `private` may be present. Every further one
+    /// is reported as [`Error::ConflictingModifiers`], or as
+    /// [`Error::RepeatedModifier`] if it repeats the first one, and only the
+    /// first one is kept.
+    fn visibility(&mut self) -> Result<Visibility> {
+        let mut vis = Visibility::empty();
+
+        while let Some(token) = self.tokens.next_if(|t| {
+            matches!(
+                t,
+                Token::Keyword(Keyword::Public(_))
+                    | Token::Keyword(Keyword::Protected(_))
+                    | Token::Keyword(Keyword::Private(_))
+            )
+        }) {
+            let (keyword, flag) = match token {
+                Token::Keyword(keyword @ Keyword::Public(_)) => (keyword, Visibility::Public),
+                Token::Keyword(keyword @ Keyword::Protected(_)) => (keyword, Visibility::Protected),
+                Token::Keyword(keyword @ Keyword::Private(_)) => (keyword, Visibility::Private),
+                _ => unreachable!(),
+            };
+            if vis.is_empty() {
+                vis.insert(flag);
+            } else if vis == flag {
+                self.compilation_unit
+                    .add_error(Error::RepeatedModifier { keyword });
+            } else {
+                self.compilation_unit
+                    .add_error(Error::ConflictingModifiers { keyword });
+            }
+        }
+
+        Ok(vis)
+    }
+
+    fn class_modifiers(&mut self) -> Result<ClassModifiers> {
+        let mut mods = ClassModifiers::empty();
+
+        while let Some(token) = self.tokens.next_if(|t| {
+            matches!(
+                t,
+                Token::Keyword(Keyword::Abstract(_))
+                    | Token::Keyword(Keyword::Final(_))
+                    | Token::Keyword(Keyword::Static(_))
+            )
+        }) {
+            match token {
+                Token::Keyword(Keyword::Abstract(_)) => mods.insert(ClassModifiers::Abstract),
+                Token::Keyword(Keyword::Final(_)) => mods.insert(ClassModifiers::Final),
+                Token::Keyword(Keyword::Static(_)) => mods.insert(ClassModifiers::Static),
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(mods)
+    }
+
+    fn field_modifiers(&mut self) -> Result<FieldModifiers> {
+        let mut mods = FieldModifiers::empty();
+
+        while let Some(token) = self.tokens.next_if(|t| {
+            matches!(
+                t,
+                Token::Keyword(Keyword::Static(_))
+                    | Token::Keyword(Keyword::Final(_))
+                    | Token::Keyword(Keyword::Transient(_))
+                    | Token::Keyword(Keyword::Volatile(_))
+            )
+        }) {
+            match token {
+                Token::Keyword(Keyword::Static(_)) => mods.insert(FieldModifiers::Static),
+                Token::Keyword(Keyword::Final(_)) => mods.insert(FieldModifiers::Final),
+                Token::Keyword(Keyword::Transient(_)) => mods.insert(FieldModifiers::Transient),
+                Token::Keyword(Keyword::Volatile(_)) => mods.insert(FieldModifiers::Volatile),
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(mods)
+    }
+
+    fn method_modifiers(&mut self) -> Result<MethodModifiers> {
+        let mut mods = MethodModifiers::empty();
+
+        while let Some(token) = self.tokens.next_if(|t| {
+            matches!(
+                t,
+                Token::Keyword(Keyword::Abstract(_))
+                    | Token::Keyword(Keyword::Final(_))
+                    | Token::Keyword(Keyword::Static(_))
+                    | Token::Keyword(Keyword::Synchronized(_))
+                    | Token::Keyword(Keyword::Native(_))
+                    | Token::Keyword(Keyword::Strictfp(_))
+            )
+        }) {
+            match token {
+                Token::Keyword(Keyword::Abstract(_)) => mods.insert(MethodModifiers::Abstract),
+                Token::Keyword(Keyword::Final(_)) => mods.insert(MethodModifiers::Final),
+                Token::Keyword(Keyword::Static(_)) => mods.insert(MethodModifiers::Static),
+                Token::Keyword(Keyword::Synchronized(_)) => {
+                    mods.insert(MethodModifiers::Synchronized)
+                }
+                Token::Keyword(Keyword::Native(_)) => mods.insert(MethodModifiers::Native),
+                Token::Keyword(Keyword::Strictfp(_)) => mods.insert(MethodModifiers::Strictfp),
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(mods)
+    }
+
+    /// Parses the comma-separated parameters of a method or constructor, up to
+    /// but not including the closing `)`. The first parameter may be an explicit
+    /// receiver parameter like `Outer this`, which is returned separately.
+    ///
+    /// The receiver of a `constructor` must be qualified like `Outer.this`,
+    /// and the one of a method must not be, otherwise it is reported.
+    fn parameters(
+        &mut self,
+        constructor: bool,
+    ) -> Result<(Option<ReceiverParameter>, Vec<Parameter>)> {
+        let mut receiver = None;
+        let mut parameters = vec![];
+        if matches!(
+            self.tokens.peek(),
+            Some(Token::Separator(Separator::RightPar(_)))
+        ) {
+            return Ok((receiver, parameters));
+        }
+
+        let mut first = true;
+        loop {
+            let (annotations, modifiers) = self.annotated_parameter_modifiers()?;
+            let parameter_type = self.r#type()?;
+            if first && self.is_at_receiver_name() {
+                let (qualifier, span) = self.receiver_name()?;
+                if constructor != qualifier.is_some() || !modifiers.is_empty() {
+                    self.compilation_unit
+                        .add_error(Error::InvalidReceiverParameter { span });
+                }
+                let mut parameter = ReceiverParameter::new(parameter_type, qualifier);
+                for annotation in annotations {
+                    parameter.add_annotation(annotation);
+                }
+                receiver = Some(parameter);
+            } else {
+                let name = self.identifier()?;
+                let mut parameter = Parameter::new(modifiers, parameter_type, name);
+                for _ in 0..self.trailing_dimensions()? {
+                    parameter.add_dimension();
+                }
+                for annotation in annotations {
+                    parameter.add_annotation(annotation);
+                }
+                parameters.push(parameter);
+            }
+
+            first = false;
+            if self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                .is_none()
+            {
+                return Ok((receiver, parameters));
+            }
+        }
+    }
+
+    /// Returns whether the next tokens are the name of a receiver parameter,
+    /// which is `this` or a qualified `Outer.this`.
+    fn is_at_receiver_name(&mut self) -> bool {
+        matches!(self.tokens.peek(), Some(Token::Keyword(Keyword::This(_))))
+            || matches!(self.tokens.peek(), Some(Token::Ident(_)))
+                && matches!(
+                    self.tokens.peek_nth(1),
+                    Some(Token::Separator(Separator::Dot(_)))
+                )
+                && matches!(
+                    self.tokens.peek_nth(2),
+                    Some(Token::Keyword(Keyword::This(_)))
+                )
+    }
+
+    /// Parses the name of a receiver parameter, and returns its qualifier,
+    /// like `Outer` in `Outer.this`, together with the span of the whole name.
+    fn receiver_name(&mut self) -> Result<(Option<Identifier>, Span)> {
+        let qualifier = match self.tokens.peek() {
+            Some(Token::Ident(_)) => {
+                let qualifier = self.identifier()?;
+                self.tokens.next(); // skip the dot
+                Some(qualifier)
+            }
+            _ => None,
+        };
+        let this =
+            self.require_token(&["this"], |t| matches!(t, Token::Keyword(Keyword::This(_))))?;
+        let start = qualifier
+            .as_ref()
+            .map_or(this.span().start(), |q| q.span().start());
+        Ok((qualifier, Span::new(start, this.span().end())))
+    }
+
+    fn parameter_modifiers(&mut self) -> Result<ParameterModifiers> {
+        let mut mods = ParameterModifiers::empty();
+
+        while self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Final(_))))
+            .is_some()
+        {
+            mods.insert(ParameterModifiers::Final);
+        }
+
+        Ok(mods)
+    }
+
+    /// Parses the modifiers of a method parameter, which may be interleaved
+    /// with annotations, like `@NotNull final`.
+    fn annotated_parameter_modifiers(&mut self) -> Result<(Vec<Annotation>, ParameterModifiers)> {
+        let mut annotations = vec![];
+        let mut mods = ParameterModifiers::empty();
+        loop {
+            match self.tokens.peek() {
+                Some(Token::Separator(Separator::At(_))) => annotations.push(self.annotation()?),
+                Some(Token::Keyword(Keyword::Final(_))) => {
+                    self.tokens.next();
+                    mods.insert(ParameterModifiers::Final);
+                }
+                _ => return Ok((annotations, mods)),
+            }
+        }
+    }
+
+    /// Parses an annotation like `@Override` or `@SuppressWarnings("all")`.
+    fn annotation(&mut self) -> Result<Annotation> {
+        let at = self.require_token(&["@"], |t| matches!(t, Token::Separator(Separator::At(_))))?;
+        let name = self.expression_name()?;
+        let mut end = name
+            .span()
+            .expect("annotation name must not be empty")
+            .end();
+
+        // the values with their optional names, and whether every name is
+        // followed by a plain `=`
+        let mut arguments = vec![];
+        let mut valid = true;
+        if self
+            .tokens
+            .next_if(|t| matches!(t, Token::Separator(Separator::LeftPar(_))))
+            .is_some()
+        {
+            if !matches!(
+                self.tokens.peek(),
+                Some(Token::Separator(Separator::RightPar(_)))
+            ) {
+                loop {
+                    let name = match self.tokens.peek_nth(1) {
+                        Some(Token::Operator(Operator::Assignment(operator))) => {
+                            let operator = *operator;
+                            let name = self.identifier()?;
+                            self.tokens.next();
+                            valid &= self.source.resolve_span(operator) == Some("=");
+                            Some(name)
+                        }
+                        _ => None,
+                    };
+                    arguments.push((name, self.element_value()?));
+                    if self
+                        .tokens
+                        .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                        .is_none()
+                    {
+                        break;
+                    }
+                }
+            }
+            let right_par = self.require_token(&[")"], |t| {
+                matches!(t, Token::Separator(Separator::RightPar(_)))
+            })?;
+            end = right_par.span().end();
+        }
+        let span = Span::new(at.span().start(), end);
+
+        // only a single value may omit the name
+        if !valid || arguments.len() > 1 && arguments.iter().any(|(name, _)| name.is_none()) {
+            return Err(Error::InvalidAnnotationElement { span });
+        }
+        let mut names = vec![];
+        let mut elements = vec![];
+        for (name, value) in arguments {
+            let Some(name) = name else {
+                elements.push(ElementValuePair::shorthand(value));
+                continue;
+            };
+            let text = self.source.resolve_span(*name.span());
+            if names.contains(&text) {
+                self.compilation_unit
+                    .add_error(Error::DuplicateAnnotationElement { span: *name.span() });
+            }
+            names.push(text);
+            elements.push(ElementValuePair::new(name, value));
+        }
+        Ok(Annotation::new(name, elements, span))
+    }
+
+    /// Parses the value of an annotation element, which is an expression, an
+    /// array of values like `{ 1, 2 }` or a nested annotation.
+    fn element_value(&mut self) -> Result<ElementValue> {
+        match self.tokens.peek() {
+            Some(Token::Separator(Separator::At(_))) => {
+                Ok(ElementValue::Annotation(self.annotation()?))
+            }
+            Some(Token::Separator(Separator::LeftCurly(_))) => {
+                Ok(ElementValue::Array(self.element_value_array()?))
+            }
+            Some(start) => {
+                let span = *start.span();
+                let expression = self.conditional()?;
+                if !is_constant_expression(&expression, self.source) {
+                    self.compilation_unit
+                        .add_error(Error::NonConstantElementValue { span });
+                }
+                Ok(ElementValue::Expression(expression))
+            }
+            None => Ok(ElementValue::Expression(self.conditional()?)),
+        }
+    }
+
+    /// Parses an array of element values like `{ 1, 2 }`, which may have a
+    /// trailing comma.
+    fn element_value_array(&mut self) -> Result<ElementValueArray> {
+        let left_curly = self.require_token(&["{"], |t| {
+            matches!(t, Token::Separator(Separator::LeftCurly(_)))
+        })?;
+        let mut values = vec![];
+        loop {
+            if let Some(right_curly) = self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::RightCurly(_))))
+            {
+                let span = Span::new(left_curly.span().start(), right_curly.span().end());
+                return Ok(ElementValueArray::new(values, span));
+            }
+            values.push(self.element_value()?);
+            if self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::Comma(_))))
+                .is_none()
+            {
+                let right_curly = self.require_token(&[",", "}"], |t| {
+                    matches!(t, Token::Separator(Separator::RightCurly(_)))
+                })?;
+                let span = Span::new(left_curly.span().start(), right_curly.span().end());
+                return Ok(ElementValueArray::new(values, span));
+            }
+        }
+    }
+
+    fn package_declaration(&mut self) -> Result<QualifiedName> {
+        let package_token = self.tokens.next().unwrap(); // skip the package token
+        debug_assert!(matches!(package_token, Token::Keyword(Keyword::Package(_))));
+
+        self.qualified_name()
+    }
+
+    fn import_declaration(&mut self) -> Result<ImportDeclaration> {
+        let import_token = self.tokens.next().unwrap(); // skip the import token
+        debug_assert!(matches!(import_token, Token::Keyword(Keyword::Import(_))));
+
+        let static_import = self
+            .tokens
+            .next_if(|t| matches!(t, Token::Keyword(Keyword::Static(_))))
+            .is_some();
+
+        let name = self.qualified_name()?;
+
+        let last_segment_span = name
+            .segments()
+            .last()
+            .expect("qualified name must have at least one segment")
+            .span();
+        let last_segment = self
+            .source
+            .resolve_span(*last_segment_span)
+            .expect("span of last segment must be valid");
+        let is_on_demand = last_segment == "*";
+
+        Ok(match (static_import, is_on_demand) {
+            (true, true) => ImportDeclaration::StaticOnDemand(name),
+            (true, false) => ImportDeclaration::StaticSingleType(name),
+            (false, true) => ImportDeclaration::OnDemand(name),
+            (false, false) => ImportDeclaration::SingleType(name),
+        })
+    }
+
+    fn qualified_name(&mut self) -> Result<QualifiedName> {
+        let mut qualified_name = QualifiedName::new();
+
+        loop {
+            // expect an identifier as first element
+            match self.tokens.next_if(|t| {
+                matches!(t, Token::Ident(_))
+                    || matches!(t, Token::Operator(Operator::Arithmetic(_)))
+            }) {
+                Some(Token::Ident(id)) => qualified_name.push(Identifier::from(id)),
+                Some(Token::Operator(Operator::Arithmetic(op))) => {
+                    let text = self.source.resolve_span(op);
+                    if text == Some("*") {
+                        qualified_name.push(Identifier::from(op))
+                    } else {
+                        return Err(Error::UnexpectedToken {
+                            expected: &["*"],
+                            found: self.tokens.peek().cloned(),
+                        });
+                    }
+                }
+                _ => {
+                    return Err(Error::UnexpectedToken {
+                        expected: &["identifier"],
+                        found: self.tokens.peek().cloned(), // as opposed to the pattern we're matching, peek returns the next token, which is what we want
+                    });
+                }
+            }
+            // after an identifier, expect a dot and then another identifier, or break
+            match self
+                .tokens
+                .next_if(|t| matches!(t, Token::Separator(Separator::Dot(_))))
+            {
+                Some(_) => {
+                    // dot is consumed
+                }
+                None => {
+                    // no dot, so we're done
+                    return Ok(qualified_name);
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether the keyword is one of the primitive types, like `int`.
+fn is_primitive_type(keyword: &Keyword) -> bool {
+    primitive_kind(keyword).is_some()
+}
+
+/// Returns the primitive type that the keyword names, like
+/// [`PrimitiveKind::Int`] for `int`.
+fn primitive_kind(keyword: &Keyword) -> Option<PrimitiveKind> {
+    Some(match keyword {
+        Keyword::Boolean(_) => PrimitiveKind::Boolean,
+        Keyword::Byte(_) => PrimitiveKind::Byte,
+        Keyword::Char(_) => PrimitiveKind::Char,
+        Keyword::Short(_) => PrimitiveKind::Short,
+        Keyword::Int(_) => PrimitiveKind::Int,
+        Keyword::Long(_) => PrimitiveKind::Long,
+        Keyword::Float(_) => PrimitiveKind::Float,
+        Keyword::Double(_) => PrimitiveKind::Double,
+        _ => return None,
+    })
+}
+
+/// Returns the precedence of a binary operator, where operators with a higher
+/// precedence bind more tightly, or `None` if the text isn't a binary operator.
+fn binary_precedence(operator: &str) -> Option<u8> {
+    Some(match operator {
+        "||" => 0,
+        "&&" => 1,
+        "|" => 2,
+        "^" => 3,
+        "&" => 4,
+        "==" | "!=" => 5,
+        "<" | ">" | "<=" | ">=" | "instanceof" => 6,
+        "<<" | ">>" | ">>>" => 7,
+        "+" | "-" => 8,
+        "*" | "/" | "%" => 9,
+        _ => return None,
+    })
+}
+
+/// Returns whether the expression may be used as a statement, which is only
+/// the case for assignments, increments, decrements, method calls and object
+/// creations.
+fn is_statement_expression(expression: &Expression, source: &Source) -> bool {
+    match expression {
+        Expression::Assignment(_) | Expression::MethodCall(_) | Expression::ObjectCreation(_) => {
+            true
+        }
+        Expression::Unary(unary) => {
+            matches!(source.resolve_span(unary.operator()), Some("++" | "--"))
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether the expression can be a constant expression, which
+/// annotation element values must be. Names are assumed to refer to constant
+/// variables or enum constants, since they can't be resolved here.
+fn is_constant_expression(expression: &Expression, source: &Source) -> bool {
+    match expression {
+        Expression::StringLiteral(_)
+        | Expression::BooleanLiteral(_)
+        | Expression::IntegerLiteral(_)
+        | Expression::Name(_) => true,
+        Expression::Parenthesized(expression) => is_constant_expression(expression, source),
+        Expression::Cast(cast) => {
+            let ty = cast.ty();
+            let is_string = ty.dimensions() == 0
+                && ty.type_arguments().is_empty()
+                && ty
+                    .name()
+                    .and_then(|name| name.resolve(source))
+                    .is_some_and(|name| name == "String" || name == "java.lang.String");
+            (ty.primitive_kind().is_some() && ty.dimensions() == 0 || is_string)
+                && is_constant_expression(cast.expression(), source)
+        }
+        Expression::Unary(unary) => {
+            !matches!(source.resolve_span(unary.operator()), Some("++" | "--"))
+                && is_constant_expression(unary.operand(), source)
+        }
+        Expression::Binary(binary) => {
+            is_constant_expression(binary.left(), source)
+                && is_constant_expression(binary.right(), source)
+        }
+        Expression::Conditional(conditional) => {
+            is_constant_expression(conditional.condition(), source)
+                && is_constant_expression(conditional.then(), source)
+                && is_constant_expression(conditional.otherwise(), source)
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether the integer literal fits into an `int`, or a `long` if it
+/// is one. A decimal literal that is `negated`, like `2147483648` in
+/// `-2147483648`, may be one larger than the maximum value, so that the
+/// minimum value can be written. Literals in other radices may use all bits
+/// of their type.
+fn is_integer_literal_in_range(text: &str, radix: Radix, long: bool, negated: bool) -> bool {
+    let digits = text.trim_end_matches(['l', 'L']).replace('_', "");
+    let (digits, base) = match radix {
+        Radix::Binary => (&digits[2..], 2),
+        Radix::Octal => (&digits[1..], 8),
+        Radix::Decimal => (&digits[..], 10),
+        Radix::Hexadecimal => (&digits[2..], 16),
+    };
+    let max = match (radix, long) {
+        (Radix::Decimal, false) => (1 << 31) - u64::from(!negated),
+        (Radix::Decimal, true) => (1 << 63) - u64::from(!negated),
+        (_, false) => u64::from(u32::MAX),
+        (_, true) => u64::MAX,
+    };
+    // a literal without digits is already reported by the lexer
+    digits.is_empty() || u64::from_str_radix(digits, base).is_ok_and(|value| value <= max)
+}
+
+/// Returns whether the keyword is reserved but has no meaning, like `goto`.
+fn is_reserved_keyword(keyword: &Keyword) -> bool {
+    matches!(keyword, Keyword::Goto(_) | Keyword::Const(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::span::Span;
+    use crate::lexer::Lexer;
+    use crate::Parser;
+    use crate::TypeKind;
+
+    macro_rules! apply_rule {
+        ($rule:expr, $input:expr) => {{
             let parser = Parser::from($input);
-            let tokens = parser.tokens();
-            let mut ctx = ParseContext::new(&parser, CompilationUnit::new(), tokens);
-            let result = $rule(&mut ctx);
-            (parser.clone(), result) // TODO: can we get rid of the clone?
+            // the context only borrows the parser for the tokens and the source,
+            // so the parser can be returned once the context is gone
+            let result = {
+                let tokens = parser.tokens();
+                let mut ctx =
+                    ParseContext::new(parser.lexer.source(), CompilationUnit::new(), tokens);
+                let result = $rule(&mut ctx);
+                // rules recover from some errors by recording them, which must not
+                // go unnoticed in tests that only look at the result
+                assert_eq!(ctx.compilation_unit.errors(), &[]);
+                result
+            };
+            (parser, result)
         }};
     }
 
     #[test]
-    fn test_qualified_name() {
-        let (parser, result) = apply_rule!(ParseContext::qualified_name, "a.b.c");
-        let name = result.expect("qualified name must parse");
+    fn test_qualified_name_synthetic() {
+        let (parser, result) = apply_rule!(ParseContext::qualified_name, "java.util.List");
+        let name = result.expect("qualified name must parse");
+        let synthetic = QualifiedName::synthetic(&["java", "util", "List"]);
+        // synthetic spans don't point into the source, but carry their text
+        let resolve = |name: &QualifiedName| {
+            name.segments()
+                .iter()
+                .map(|s| {
+                    let span = *s.span();
+                    span.synthetic_text()
+                        .or_else(|| parser.resolve_span(span))
+                        .unwrap()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(resolve(&name), resolve(&synthetic));
+        assert_ne!(name, synthetic);
+    }
+
+    #[test]
+    fn test_qualified_name() {
+        let (parser, result) = apply_rule!(ParseContext::qualified_name, "a.b.c");
+        let name = result.expect("qualified name must parse");
+        assert_eq!(
+            name.segments()
+                .iter()
+                .map(|s| parser.resolve_spanned(s))
+                .map(|s| s.unwrap())
+                .collect::<Vec<_>>()
+                .as_slice(),
+            &["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn test_incomplete_qualified_name_eof() {
+        let (_, result) = apply_rule!(ParseContext::qualified_name, "a.b.");
+        assert_eq!(
+            result,
+            Err(Error::UnexpectedToken {
+                expected: &["identifier"],
+                found: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_incomplete_qualified_name() {
+        let (_, result) = apply_rule!(ParseContext::qualified_name, "a.b.;");
+        assert_eq!(
+            result,
+            Err(Error::UnexpectedToken {
+                expected: &["identifier"],
+                found: Some(Token::Separator(Separator::Semicolon(Span::new(4, 5)))),
+            })
+        );
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        for (input, expected) in &[
+            ("a.b.c", QualifiedName::from(vec![(0, 1), (2, 3), (4, 5)])),
+            ("a.b.*", QualifiedName::from(vec![(0, 1), (2, 3), (4, 5)])),
+            (
+                "a .b . c",
+                QualifiedName::from(vec![(0, 1), (3, 4), (7, 8)]),
+            ),
+            (
+                "a.b.c hello world",
+                QualifiedName::from(vec![(0, 1), (2, 3), (4, 5)]),
+            ),
+            (
+                "hello.world.Foobar",
+                QualifiedName::from(vec![(0, 5), (6, 11), (12, 18)]),
+            ),
+        ] {
+            let (_, output) = apply_rule!(ParseContext::qualified_name, *input);
+            assert_eq!(output.unwrap(), *expected);
+        }
+    }
+
+    #[test]
+    fn test_context_resolves_with_source() {
+        let lexer = Lexer::from("class A { int x = 1 + 2; }");
+        let tokens = TokenStream::new(lexer.tokens());
+        let mut ctx = ParseContext::new(lexer.source(), CompilationUnit::new(), tokens);
+        ctx.parse();
+        let compilation_unit = CompilationUnit::from(ctx);
+        assert!(!compilation_unit.has_errors());
+
+        let TypeDeclaration::Class(class) = &compilation_unit.types()[0] else {
+            panic!("expected a class");
+        };
+        let resolve = |spanned: &dyn Spanned| lexer.source().resolve_span(spanned.span()?);
+        assert_eq!(resolve(class.name()), Some("A"));
+        let ClassMember::Field(field) = &class.members()[0] else {
+            panic!("expected a field");
+        };
+        // the operator is resolved while parsing, to find its precedence
+        let Some(Expression::Binary(binary)) = field.variables()[0].initializer() else {
+            panic!("expected a binary initializer");
+        };
+        assert_eq!(lexer.source().resolve_span(binary.operator()), Some("+"));
+    }
+
+    #[test]
+    fn test_qualified_name_not_consume_after() {
+        let lexer = Lexer::from("a.b.c;");
+        let parser = Parser::from(lexer);
+        let tokens = parser.tokens();
+        let mut ctx = ParseContext::new(parser.lexer.source(), CompilationUnit::new(), tokens);
+        let qualified_name = ctx.qualified_name().unwrap();
+        assert_eq!(
+            qualified_name,
+            QualifiedName::from(vec![Span::new(0, 1), Span::new(2, 3), Span::new(4, 5)]),
+        );
+
+        // ParseContext::qualified_name must not consume the token after the qualified name
+        assert_eq!(
+            ctx.tokens
+                .next()
+                .expect("expected the semicolon at the end"),
+            Token::Separator(Separator::Semicolon(Span::new(5, 6)))
+        );
+    }
+
+    #[test]
+    fn test_method_call_on_literal() {
+        let (parser, result) = apply_rule!(ParseContext::expression, r#""hi".length()"#);
+        let call = match result.expect("expression must parse") {
+            Expression::MethodCall(call) => call,
+            e => panic!("expected a method call, got {:?}", e),
+        };
+        assert_eq!(
+            call.receiver(),
+            Some(&Expression::StringLiteral(StringLiteral::new(Span::new(
+                0, 4
+            ))))
+        );
+        assert_eq!(parser.resolve_spanned(call.name()), Some("length"));
+        assert!(call.arguments().is_empty());
+    }
+
+    #[test]
+    fn test_unqualified_method_call() {
+        let (parser, result) = apply_rule!(ParseContext::expression, "sqrt(2)");
+        let Ok(Expression::MethodCall(call)) = result else {
+            panic!("expected a method call, got {:?}", result);
+        };
+        assert!(call.receiver().is_none());
+        assert_eq!(
+            parser.resolve_all(call.name().segments()),
+            vec![Some("sqrt")]
+        );
+        let [Expression::IntegerLiteral(argument)] = call.arguments() else {
+            panic!(
+                "expected a single integer argument, got {:?}",
+                call.arguments()
+            );
+        };
+        assert_eq!(parser.resolve_spanned(argument), Some("2"));
+    }
+
+    #[test]
+    fn test_conditional_argument() {
+        let (_, result) = apply_rule!(ParseContext::expression, "f(a ? b : c, d)");
+        let Ok(Expression::MethodCall(call)) = result else {
+            panic!("expected a method call, got {:?}", result);
+        };
+        assert!(matches!(
+            call.arguments(),
+            [Expression::Conditional(_), Expression::Name(_)]
+        ));
+
+        let (_, result) = apply_rule!(ParseContext::expression, "f(a = b ? c : d, e = f)");
+        let Ok(Expression::MethodCall(call)) = result else {
+            panic!("expected a method call, got {:?}", result);
+        };
+        let [Expression::Assignment(first), Expression::Assignment(_)] = call.arguments() else {
+            panic!("expected two assignments, got {:?}", call.arguments());
+        };
+        assert!(matches!(first.value(), Expression::Conditional(_)));
+    }
+
+    #[test]
+    fn test_method_call_chains() {
+        let (_, result) = apply_rule!(
+            ParseContext::expression,
+            r#"new StringBuilder().append("x").length"#
+        );
+        assert_eq!(
+            result,
+            Ok(Expression::FieldAccess(FieldAccess::new(
+                Expression::MethodCall(MethodCall::new(
+                    Some(Expression::ObjectCreation(ObjectCreation::new(
+                        Type::from(QualifiedName::from(vec![(4, 17)])),
+                        vec![]
+                    ))),
+                    QualifiedName::from(vec![(20, 26)]),
+                    vec![Expression::StringLiteral(StringLiteral::new(Span::new(
+                        27, 30
+                    )))],
+                )),
+                Identifier::from((32, 38)),
+            )))
+        );
+
+        let (_, result) = apply_rule!(ParseContext::expression, "(a).b(c, d.e)");
+        assert_eq!(
+            result,
+            Ok(Expression::MethodCall(MethodCall::new(
+                Some(Expression::Parenthesized(Box::new(Expression::Name(
+                    QualifiedName::from(vec![(1, 2)])
+                )))),
+                QualifiedName::from(vec![(4, 5)]),
+                vec![
+                    Expression::Name(QualifiedName::from(vec![(6, 7)])),
+                    Expression::Name(QualifiedName::from(vec![(9, 10), (11, 12)])),
+                ],
+            )))
+        );
+    }
+
+    #[test]
+    fn test_generic_cast() {
+        let (parser, result) = apply_rule!(ParseContext::expression, "(Map<K,V>) o");
+        let cast = match result.expect("cast must parse") {
+            Expression::Cast(cast) => cast,
+            e => panic!("expected a cast, got {:?}", e),
+        };
+        assert_eq!(parser.resolve_spanned(cast.ty()), Some("Map<K,V>"));
+        assert_eq!(
+            parser.resolve_spanned(cast.ty().name().unwrap()),
+            Some("Map")
+        );
+        assert_eq!(
+            cast.ty()
+                .type_arguments()
+                .iter()
+                .map(|t| parser.resolve_spanned(t).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["K", "V"]
+        );
+        assert_eq!(
+            cast.expression(),
+            &Expression::Name(QualifiedName::from(vec![(11, 12)]))
+        );
+    }
+
+    #[test]
+    fn test_cast_or_parenthesized() {
+        for (input, is_cast) in [
+            ("(String) o", true),
+            ("(int) o", true),
+            ("(java.util.List<String>) o.get()", true),
+            ("(a)", false),
+            ("(a).b()", false),
+        ] {
+            let (_, result) = apply_rule!(ParseContext::expression, input);
+            let result = result.expect("expression must parse");
+            assert_eq!(
+                matches!(result, Expression::Cast(_)),
+                is_cast,
+                "{} parsed as {:?}",
+                input,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn test_integer_literal_arguments() {
+        let (_, result) = apply_rule!(ParseContext::expression, "f(0x1F, 0b1, 017, 9)");
+        let call = match result.expect("method call must parse") {
+            Expression::MethodCall(call) => call,
+            e => panic!("expected a method call, got {:?}", e),
+        };
+        assert_eq!(
+            call.arguments(),
+            &[
+                Expression::IntegerLiteral(IntegerLiteral::new(
+                    Span::new(2, 6),
+                    Radix::Hexadecimal,
+                    false
+                )),
+                Expression::IntegerLiteral(IntegerLiteral::new(
+                    Span::new(8, 11),
+                    Radix::Binary,
+                    false
+                )),
+                Expression::IntegerLiteral(IntegerLiteral::new(
+                    Span::new(13, 16),
+                    Radix::Octal,
+                    false
+                )),
+                Expression::IntegerLiteral(IntegerLiteral::new(
+                    Span::new(18, 19),
+                    Radix::Decimal,
+                    false
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_integer_literal() {
+        let (_, result) = apply_rule!(ParseContext::expression, "f(42L, 0xFFL, 7)");
+        let call = match result.expect("method call must parse") {
+            Expression::MethodCall(call) => call,
+            e => panic!("expected a method call, got {:?}", e),
+        };
+        let longs = call
+            .arguments()
+            .iter()
+            .map(|argument| match argument {
+                Expression::IntegerLiteral(literal) => literal.is_long(),
+                e => panic!("expected an integer literal, got {:?}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(longs, vec![true, true, false]);
+    }
+
+    #[test]
+    fn test_anonymous_class_body() {
+        let (_, result) = apply_rule!(ParseContext::expression, "new Runnable() { }");
+        let creation = match result.expect("object creation must parse") {
+            Expression::ObjectCreation(creation) => creation,
+            e => panic!("expected an object creation, got {:?}", e),
+        };
+        let body = creation
+            .body()
+            .expect("anonymous class body must be parsed");
+        assert!(body.members().is_empty());
+
+        let (_, result) = apply_rule!(
+            ParseContext::expression,
+            "new Runnable() { public void run() {} }"
+        );
+        let creation = match result.expect("object creation must parse") {
+            Expression::ObjectCreation(creation) => creation,
+            e => panic!("expected an object creation, got {:?}", e),
+        };
+        let body = creation
+            .body()
+            .expect("anonymous class body must be parsed");
+        assert_eq!(body.members().len(), 1);
+
+        let (_, result) = apply_rule!(ParseContext::expression, "new Object()");
+        match result.expect("object creation must parse") {
+            Expression::ObjectCreation(creation) => assert!(creation.body().is_none()),
+            e => panic!("expected an object creation, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_null_literal() {
+        let (_, result) = apply_rule!(ParseContext::expression, "f(null)");
+        match result.expect("method call must parse") {
+            Expression::MethodCall(call) => assert_eq!(
+                call.arguments(),
+                &[Expression::NullLiteral(NullLiteral::new(Span::new(2, 6)))]
+            ),
+            e => panic!("expected a method call, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_conditional_array_index() {
+        let (_, result) = apply_rule!(ParseContext::expression, "a[x ? 1 : 2]");
+        let access = match result.expect("array access must parse") {
+            Expression::ArrayAccess(access) => access,
+            e => panic!("expected an array access, got {:?}", e),
+        };
+        assert!(matches!(access.array(), Expression::Name(_)));
+        let conditional = match access.index() {
+            Expression::Conditional(conditional) => conditional,
+            e => panic!("expected a conditional index, got {:?}", e),
+        };
+        assert!(matches!(conditional.condition(), Expression::Name(_)));
+        assert!(matches!(conditional.then(), Expression::IntegerLiteral(_)));
+        assert!(matches!(
+            conditional.otherwise(),
+            Expression::IntegerLiteral(_)
+        ));
+    }
+
+    #[test]
+    fn test_nested_conditional() {
+        // the conditional operator is right associative
+        let (_, result) = apply_rule!(ParseContext::expression, "a ? b : c ? d : e");
+        let conditional = match result.expect("conditional must parse") {
+            Expression::Conditional(conditional) => conditional,
+            e => panic!("expected a conditional, got {:?}", e),
+        };
+        assert!(matches!(conditional.then(), Expression::Name(_)));
+        assert!(matches!(
+            conditional.otherwise(),
+            Expression::Conditional(_)
+        ));
+    }
+
+    #[test]
+    fn test_switch_null_and_default_labels() {
+        let (_, result) = apply_rule!(
+            ParseContext::statement,
+            "switch (o) { case null -> a(); default -> b(); }"
+        );
+        let statement = result.expect("switch must parse");
+        let switch = match statement.kind() {
+            StatementKind::Switch(switch) => switch,
+            s => panic!("expected a switch, got {:?}", s),
+        };
+        assert!(matches!(switch.selector(), Expression::Name(_)));
+        let cases = switch.cases();
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].labels(), &[CaseLabel::Null]);
+        assert!(cases[0].is_arrow());
+        assert_eq!(cases[0].statements().len(), 1);
+        assert_eq!(cases[1].labels(), &[CaseLabel::Default]);
+    }
+
+    #[test]
+    fn test_switch_case_labels() {
+        let (parser, result) = apply_rule!(
+            ParseContext::statement,
+            "switch (o) { case null, default -> {} case String s -> f(s); case A, B: g(); h(); }"
+        );
+        let statement = result.expect("switch must parse");
+        let switch = match statement.kind() {
+            StatementKind::Switch(switch) => switch,
+            s => panic!("expected a switch, got {:?}", s),
+        };
+        let cases = switch.cases();
+        assert_eq!(cases.len(), 3);
+        assert_eq!(cases[0].labels(), &[CaseLabel::Null, CaseLabel::Default]);
+        match cases[1].labels() {
+            [CaseLabel::TypePattern(ty, name)] => {
+                assert_eq!(parser.resolve_spanned(ty), Some("String"));
+                assert_eq!(parser.resolve_spanned(name), Some("s"));
+            }
+            labels => panic!("expected a type pattern, got {:?}", labels),
+        }
+        assert!(matches!(
+            cases[2].labels(),
+            [CaseLabel::Expression(_), CaseLabel::Expression(_)]
+        ));
+        assert!(!cases[2].is_arrow());
+        assert_eq!(cases[2].statements().len(), 2);
+    }
+
+    #[test]
+    fn test_final_local_variable() {
+        let (parser, result) = apply_rule!(ParseContext::statement, "final int x = 1, y;");
+        let statement = result.expect("local variable declaration must parse");
+        let declaration = match statement.kind() {
+            StatementKind::LocalVariableDeclaration(declaration) => declaration,
+            s => panic!("expected a local variable declaration, got {:?}", s),
+        };
+        assert_eq!(*declaration.modifiers(), ParameterModifiers::Final);
+        assert_eq!(parser.resolve_spanned(declaration.ty()), Some("int"));
+        let variables = declaration.variables();
+        assert_eq!(variables.len(), 2);
+        assert_eq!(parser.resolve_spanned(variables[0].name()), Some("x"));
+        assert!(variables[0].value().is_some());
+        assert!(variables[1].value().is_none());
+
+        let (_, result) = apply_rule!(ParseContext::statement, "int x;");
+        match result
+            .expect("local variable declaration must parse")
+            .kind()
+        {
+            StatementKind::LocalVariableDeclaration(declaration) => {
+                assert_eq!(*declaration.modifiers(), ParameterModifiers::empty())
+            }
+            s => panic!("expected a local variable declaration, got {:?}", s),
+        }
+    }
+
+    #[test]
+    fn test_final_catch_parameter() {
+        let (parser, result) = apply_rule!(
+            ParseContext::statement,
+            "try {} catch (final Exception e) {} catch (A | B e) {} finally {}"
+        );
+        let statement = result.expect("try statement must parse");
+        let try_statement = match statement.kind() {
+            StatementKind::Try(try_statement) => try_statement,
+            s => panic!("expected a try statement, got {:?}", s),
+        };
+        let catches = try_statement.catches();
+        assert_eq!(catches.len(), 2);
+        assert_eq!(
+            *catches[0].parameter().modifiers(),
+            ParameterModifiers::Final
+        );
+        assert_eq!(
+            *catches[1].parameter().modifiers(),
+            ParameterModifiers::empty()
+        );
+        assert_eq!(
+            catches[1]
+                .parameter()
+                .ty()
+                .iter()
+                .map(|t| parser.resolve_spanned(t).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["A", "B"]
+        );
+        assert!(try_statement.finally().is_some());
+
+        let (_, result) = apply_rule!(ParseContext::statement, "try {}");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_caught_types() {
+        let (parser, result) = apply_rule!(
+            ParseContext::statement,
+            "try {} catch (A | B e) {} catch (java.io.C e) {}"
+        );
+        let statement = result.expect("try statement must parse");
+        let StatementKind::Try(try_statement) = statement.kind() else {
+            panic!("expected a try statement, got {:?}", statement);
+        };
+        assert_eq!(
+            try_statement.caught_types(parser.source()),
+            vec!["A", "B", "java.io.C"]
+        );
+    }
+
+    #[test]
+    fn test_final_for_each_variable() {
+        let (parser, result) =
+            apply_rule!(ParseContext::statement, "for (final String s : list) {}");
+        let statement = result.expect("for statement must parse");
+        let for_each = match statement.kind() {
+            StatementKind::ForEach(for_each) => for_each,
+            s => panic!("expected a for each statement, got {:?}", s),
+        };
+        assert_eq!(*for_each.variable().modifiers(), ParameterModifiers::Final);
+        assert_eq!(
+            parser.resolve_spanned(for_each.variable().ty()),
+            Some("String")
+        );
+        assert_eq!(
+            parser.resolve_spanned(for_each.variable().name()),
+            Some("s")
+        );
+        assert!(matches!(for_each.expression(), Expression::Name(_)));
+    }
+
+    #[test]
+    fn test_generic_for_each() {
+        let (parser, result) = apply_rule!(
+            ParseContext::statement,
+            "for (Map.Entry<K,V> e : m.entrySet()) {}"
+        );
+        let statement = result.expect("for statement must parse");
+        let StatementKind::ForEach(for_each) = statement.kind() else {
+            panic!("expected a for each statement, got {:?}", statement);
+        };
+        let ty = for_each.variable().ty();
+        assert_eq!(parser.resolve_spanned(ty), Some("Map.Entry<K,V>"));
+        assert_eq!(
+            ty.name().map(|name| parser.resolve_all(name.segments())),
+            Some(vec![Some("Map"), Some("Entry")])
+        );
+        assert_eq!(ty.type_arguments().len(), 2);
+        let Expression::MethodCall(call) = for_each.expression() else {
+            panic!("expected a method call, got {:?}", for_each.expression());
+        };
+        assert_eq!(
+            parser.resolve_all(call.name().segments()),
+            vec![Some("m"), Some("entrySet")]
+        );
+        assert!(call.arguments().is_empty());
+    }
+
+    #[test]
+    fn test_basic_for() {
+        let (_, result) = apply_rule!(ParseContext::statement, "for (int i = 0; b; ) f(i);");
+        let statement = result.expect("for statement must parse");
+        let for_statement = match statement.kind() {
+            StatementKind::For(for_statement) => for_statement,
+            s => panic!("expected a for statement, got {:?}", s),
+        };
+        assert!(matches!(
+            for_statement.initializers(),
+            [ForInitializer::LocalVariableDeclaration(_)]
+        ));
+        assert!(for_statement.condition().is_some());
+        assert!(for_statement.updaters().is_empty());
+        assert_eq!(for_statement.block().statements().len(), 1);
+
+        let (parser, result) =
+            apply_rule!(ParseContext::statement, "for (int i = 0; i < 10; i++) {}");
+        let statement = result.expect("for statement must parse");
+        let StatementKind::For(for_statement) = statement.kind() else {
+            panic!("expected a for statement, got {:?}", statement);
+        };
+        let [Expression::Unary(increment)] = for_statement.updaters() else {
+            panic!("expected an increment, got {:?}", for_statement.updaters());
+        };
+        assert!(increment.is_postfix());
+        assert_eq!(parser.resolve_span(increment.operator()), Some("++"));
+    }
+
+    #[test]
+    fn test_compound_type_parameter_bounds() {
+        let (parser, result) = apply_rule!(
+            ParseContext::type_declaration,
+            "class C<T extends Comparable<T> & Serializable> {}"
+        );
+        let class = match result.expect("class must parse") {
+            TypeDeclaration::Class(class) => class,
+            ty => panic!("expected a class, got {:?}", ty),
+        };
+        let [type_parameter] = class.type_parameters() else {
+            panic!(
+                "expected one type parameter, got {:?}",
+                class.type_parameters()
+            );
+        };
+        assert_eq!(parser.resolve_spanned(type_parameter.name()), Some("T"));
+        let bounds = type_parameter
+            .bounds()
+            .iter()
+            .map(|bound| parser.resolve_spanned(bound))
+            .collect::<Vec<_>>();
+        assert_eq!(bounds, vec![Some("Comparable<T>"), Some("Serializable")]);
+    }
+
+    #[test]
+    fn test_annotation_declaration() {
+        let (parser, result) = apply_rule!(
+            ParseContext::type_declaration,
+            "public @interface A { Class<?>[] types(); int LIMIT = 1; }"
+        );
+        let TypeDeclaration::Annotation(annotation) = result.expect("annotation type must parse")
+        else {
+            panic!("expected an annotation type");
+        };
+        assert_eq!(*annotation.visibility(), Visibility::Public);
+        assert_eq!(parser.resolve_spanned(annotation.name()), Some("A"));
+        let [AnnotationMember::Element(element), AnnotationMember::Field(_)] = annotation.members()
+        else {
+            panic!(
+                "expected an element and a constant, got {:?}",
+                annotation.members()
+            );
+        };
+        assert_eq!(parser.resolve_spanned(element.name()), Some("types"));
+        let element_type = element.element_type();
+        assert_eq!(parser.resolve_spanned(element_type), Some("Class<?>[]"));
+        assert_eq!(element_type.dimensions(), 1);
+        assert!(matches!(
+            element_type.type_arguments(),
+            [TypeArgument::Wildcard(_)]
+        ));
+        assert!(element.default().is_none());
+
+        let (parser, result) = apply_rule!(
+            ParseContext::type_declaration,
+            r#"@interface A { int value() default 1; String[] names()[] default { "a" }; }"#
+        );
+        let TypeDeclaration::Annotation(annotation) = result.expect("annotation type must parse")
+        else {
+            panic!("expected an annotation type");
+        };
+        let [AnnotationMember::Element(value), AnnotationMember::Element(names)] =
+            annotation.members()
+        else {
+            panic!("expected two elements, got {:?}", annotation.members());
+        };
+        assert!(matches!(
+            value.default(),
+            Some(ElementValue::Expression(Expression::IntegerLiteral(_)))
+        ));
+        assert_eq!(names.dimensions(), 1);
+        let Some(ElementValue::Array(default)) = names.default() else {
+            panic!("expected an array default, got {:?}", names.default());
+        };
+        assert_eq!(parser.resolve_spanned(default), Some(r#"{ "a" }"#));
+    }
+
+    #[test]
+    fn test_generic_declarations() {
+        let (parser, result) = apply_rule!(ParseContext::type_declaration, "class Box<T> {}");
+        let TypeDeclaration::Class(class) = result.expect("class must parse") else {
+            panic!("expected a class");
+        };
+        assert_eq!(
+            class
+                .type_parameters()
+                .iter()
+                .map(|type_parameter| parser.resolve_spanned(type_parameter.name()))
+                .collect::<Vec<_>>(),
+            vec![Some("T")]
+        );
+        assert!(class.type_parameters()[0].bounds().is_empty());
+
+        let (parser, result) = apply_rule!(
+            ParseContext::type_declaration,
+            "interface Pair<K, V> { K key(); }"
+        );
+        let TypeDeclaration::Interface(interface) = result.expect("interface must parse") else {
+            panic!("expected an interface");
+        };
+        assert_eq!(
+            interface
+                .type_parameters()
+                .iter()
+                .map(|type_parameter| parser.resolve_spanned(type_parameter.name()))
+                .collect::<Vec<_>>(),
+            vec![Some("K"), Some("V")]
+        );
+
+        let (parser, result) = apply_rule!(
+            ParseContext::class_member,
+            "public static <T extends Number> T max(T a, T b) {}"
+        );
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        let [type_parameter] = method.type_parameters() else {
+            panic!(
+                "expected one type parameter, got {:?}",
+                method.type_parameters()
+            );
+        };
+        assert_eq!(parser.resolve_spanned(type_parameter.name()), Some("T"));
+        assert_eq!(
+            parser.resolve_all(type_parameter.bounds()),
+            vec![Some("Number")]
+        );
+        assert_eq!(parser.resolve_spanned(method.name()), Some("max"));
+        assert_eq!(
+            parser.resolve_spanned(method.return_type().expect("method returns T")),
+            Some("T")
+        );
+
+        let (_, result) = apply_rule!(
+            ParseContext::class_member,
+            "<T extends Comparable<T>> A(T t) {}"
+        );
+        let ClassMember::Constructor(constructor) = result.expect("constructor must parse") else {
+            panic!("expected a constructor");
+        };
+        assert_eq!(constructor.type_parameters().len(), 1);
+    }
+
+    #[test]
+    fn test_receiver_parameter() {
+        let (parser, result) = apply_rule!(
+            ParseContext::class_member,
+            "void f(Outer.Inner this, int a) {}"
+        );
+        let method = match result.expect("method must parse") {
+            ClassMember::Method(method) => method,
+            member => panic!("expected a method, got {:?}", member),
+        };
+        let receiver = method.receiver().expect("method must have a receiver");
+        assert_eq!(parser.resolve_spanned(receiver.ty()), Some("Outer.Inner"));
+        assert!(receiver.qualifier().is_none());
+        assert_eq!(method.parameters().len(), 1);
+        assert_eq!(
+            parser.resolve_spanned(method.parameters()[0].name()),
+            Some("a")
+        );
+
+        // only the first parameter can be a receiver
+        let (_, result) = apply_rule!(ParseContext::class_member, "void f(int a, Outer this) {}");
+        assert!(result.is_err());
+
+        let (parser, result) =
+            apply_rule!(ParseContext::class_member, "void f(@A @B(1) Foo this) {}");
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        let receiver = method.receiver().expect("method must have a receiver");
+        assert_eq!(
+            parser.resolve_all(receiver.annotations()),
+            vec![Some("@A"), Some("@B(1)")]
+        );
+        assert!(method.parameters().is_empty());
+    }
+
+    #[test]
+    fn test_block_span() {
+        let (parser, result) = apply_rule!(ParseContext::class_member, "void f() { }");
+        let method = match result.expect("method must parse") {
+            ClassMember::Method(method) => method,
+            member => panic!("expected a method, got {:?}", member),
+        };
+        let block = method.block().expect("method must have a body");
+        assert_eq!(parser.resolve_spanned(block), Some("{ }"));
+
+        let (parser, result) = apply_rule!(ParseContext::block, "{ f(); { } }");
+        let block = result.expect("block must parse");
+        assert_eq!(parser.resolve_spanned(&block), Some("{ f(); { } }"));
+    }
+
+    #[test]
+    fn test_class_extends() {
+        let (parser, result) = apply_rule!(ParseContext::type_declaration, "class A extends B {}");
+        let class = match result.expect("class must parse") {
+            TypeDeclaration::Class(class) => class,
+            ty => panic!("expected a class, got {:?}", ty),
+        };
+        let superclass = class.extends().expect("class must have a superclass");
+        assert_eq!(parser.resolve_spanned(superclass), Some("B"));
+
+        let (parser, result) = apply_rule!(
+            ParseContext::type_declaration,
+            "class A<T> extends java.util.AbstractList {}"
+        );
+        let class = match result.expect("class must parse") {
+            TypeDeclaration::Class(class) => class,
+            ty => panic!("expected a class, got {:?}", ty),
+        };
+        let superclass = class.extends().expect("class must have a superclass");
+        assert_eq!(
+            parser.resolve_spanned(superclass),
+            Some("java.util.AbstractList")
+        );
+    }
+
+    #[test]
+    fn test_class_implements() {
+        let (parser, result) = apply_rule!(
+            ParseContext::type_declaration,
+            "class A extends B implements C, D.E {}"
+        );
+        let class = match result.expect("class must parse") {
+            TypeDeclaration::Class(class) => class,
+            ty => panic!("expected a class, got {:?}", ty),
+        };
+        assert_eq!(parser.resolve_spanned(class.extends().unwrap()), Some("B"));
+        let interfaces = class
+            .implements()
+            .iter()
+            .map(|name| parser.resolve_spanned(name))
+            .collect::<Vec<_>>();
+        assert_eq!(interfaces, vec![Some("C"), Some("D.E")]);
+        assert!(class.members().is_empty());
+    }
+
+    #[test]
+    fn test_binary_precedence() {
+        let (parser, result) = apply_rule!(ParseContext::expression, "a = b || c + d * e - f");
+        let Expression::Assignment(assignment) = result.expect("expression must parse") else {
+            panic!("expected an assignment");
+        };
+        let Expression::Binary(or) = assignment.value() else {
+            panic!("expected a binary expression");
+        };
+        assert_eq!(parser.resolve_span(or.operator()), Some("||"));
+        // subtraction is left associative, so it is applied last
+        let Expression::Binary(minus) = or.right() else {
+            panic!("expected a binary expression");
+        };
+        assert_eq!(parser.resolve_span(minus.operator()), Some("-"));
+        let Expression::Binary(plus) = minus.left() else {
+            panic!("expected a binary expression");
+        };
+        assert_eq!(parser.resolve_span(plus.operator()), Some("+"));
+        let Expression::Binary(times) = plus.right() else {
+            panic!("expected a binary expression");
+        };
+        assert_eq!(parser.resolve_span(times.operator()), Some("*"));
+    }
+
+    #[test]
+    fn test_unary_expressions() {
+        let (parser, result) = apply_rule!(ParseContext::expression, "-a * !b[0] - ~c--");
+        let Expression::Binary(minus) = result.expect("expression must parse") else {
+            panic!("expected a binary expression");
+        };
+        let Expression::Binary(times) = minus.left() else {
+            panic!("expected a binary expression");
+        };
+        let Expression::Unary(negation) = times.left() else {
+            panic!("expected a unary expression");
+        };
+        assert_eq!(parser.resolve_span(negation.operator()), Some("-"));
+        assert!(!negation.is_postfix());
+        // postfix operators bind more tightly than prefix operators
+        let Expression::Unary(not) = times.right() else {
+            panic!("expected a unary expression");
+        };
+        assert!(matches!(not.operand(), Expression::ArrayAccess(_)));
+        let Expression::Unary(complement) = minus.right() else {
+            panic!("expected a unary expression");
+        };
+        assert_eq!(parser.resolve_span(complement.operator()), Some("~"));
+        let Expression::Unary(decrement) = complement.operand() else {
+            panic!("expected a unary expression");
+        };
+        assert!(decrement.is_postfix());
+
+        let (_, result) = apply_rule!(ParseContext::statement, "i++;");
+        let statement = result.expect("statement must parse");
+        assert!(matches!(
+            statement.kind(),
+            StatementKind::Expression(Expression::Unary(_))
+        ));
+        let (_, result) = apply_rule!(ParseContext::statement, "--i;");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_instance_of() {
+        let (parser, result) = apply_rule!(
+            ParseContext::expression,
+            "a instanceof List<String> && a != b"
+        );
+        let Expression::Binary(and) = result.expect("expression must parse") else {
+            panic!("expected a binary expression");
+        };
+        assert_eq!(parser.resolve_span(and.operator()), Some("&&"));
+        let Expression::InstanceOf(instance_of) = and.left() else {
+            panic!("expected an instanceof expression, got {:?}", and.left());
+        };
+        assert!(matches!(instance_of.expression(), Expression::Name(_)));
         assert_eq!(
-            name.segments()
-                .iter()
-                .map(|s| parser.resolve_spanned(s))
-                .map(|s| s.unwrap())
-                .collect::<Vec<_>>()
-                .as_slice(),
-            &["a", "b", "c"]
+            parser.resolve_spanned(instance_of.ty().name().unwrap()),
+            Some("List")
         );
     }
 
     #[test]
-    fn test_incomplete_qualified_name_eof() {
-        let (_, result) = apply_rule!(ParseContext::qualified_name, "a.b.");
+    fn test_field_declaration() {
+        let (parser, result) = apply_rule!(ParseContext::class_member, "private int x = 5;");
+        let field = match result.expect("field must parse") {
+            ClassMember::Field(field) => field,
+            member => panic!("expected a field, got {:?}", member),
+        };
+        assert_eq!(*field.visibility(), Visibility::Private);
+        assert_eq!(parser.resolve_spanned(field.field_type()), Some("int"));
+        let [variable] = field.variables() else {
+            panic!("expected a single variable, got {:?}", field.variables());
+        };
+        assert_eq!(parser.resolve_spanned(variable.name()), Some("x"));
+        let Some(Expression::IntegerLiteral(literal)) = variable.initializer() else {
+            panic!(
+                "expected an integer literal, got {:?}",
+                variable.initializer()
+            );
+        };
+        assert_eq!(parser.resolve_spanned(literal), Some("5"));
+
+        let (_, result) = apply_rule!(
+            ParseContext::class_member,
+            "public static final transient String s;"
+        );
+        let field = match result.expect("field must parse") {
+            ClassMember::Field(field) => field,
+            member => panic!("expected a field, got {:?}", member),
+        };
+        assert_eq!(
+            *field.modifiers(),
+            FieldModifiers::Static | FieldModifiers::Final | FieldModifiers::Transient
+        );
+        assert!(field.variables()[0].initializer().is_none());
+    }
+
+    #[test]
+    fn test_primitive_and_reference_types() {
+        let (_, result) = apply_rule!(ParseContext::class_member, "int x;");
+        let field = match result.expect("field must parse") {
+            ClassMember::Field(field) => field,
+            member => panic!("expected a field, got {:?}", member),
+        };
+        assert_eq!(
+            field.field_type().kind(),
+            &TypeKind::Primitive(PrimitiveKind::Int)
+        );
+        assert_eq!(field.field_type().name(), None);
+
+        let (parser, result) = apply_rule!(ParseContext::class_member, "String x;");
+        let field = match result.expect("field must parse") {
+            ClassMember::Field(field) => field,
+            member => panic!("expected a field, got {:?}", member),
+        };
+        let TypeKind::Reference(name) = field.field_type().kind() else {
+            panic!("expected a reference type, got {:?}", field.field_type());
+        };
+        assert_eq!(parser.resolve_spanned(name), Some("String"));
+        assert_eq!(field.field_type().primitive_kind(), None);
+
+        let (_, result) = apply_rule!(
+            ParseContext::class_member,
+            "double f(boolean a, String b) {}"
+        );
+        let method = match result.expect("method must parse") {
+            ClassMember::Method(method) => method,
+            member => panic!("expected a method, got {:?}", member),
+        };
+        assert_eq!(
+            method.return_type().and_then(Type::primitive_kind),
+            Some(PrimitiveKind::Double)
+        );
+        let kinds = method
+            .parameters()
+            .iter()
+            .map(|parameter| parameter.parameter_type().primitive_kind())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![Some(PrimitiveKind::Boolean), None]);
+    }
+
+    #[test]
+    fn test_field_declaration_multiple_variables() {
+        let (parser, result) = apply_rule!(ParseContext::class_member, "int a[], b = 2, c;");
+        let field = match result.expect("field must parse") {
+            ClassMember::Field(field) => field,
+            member => panic!("expected a field, got {:?}", member),
+        };
+        assert_eq!(parser.resolve_spanned(field.field_type()), Some("int"));
+        let names = field
+            .variables()
+            .iter()
+            .map(|variable| parser.resolve_spanned(variable.name()))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![Some("a"), Some("b"), Some("c")]);
+
+        let [a, b, c] = field.variables() else {
+            unreachable!();
+        };
+        assert_eq!(a.dimensions(), 1);
+        assert_eq!(b.dimensions(), 0);
+        assert!(a.initializer().is_none());
+        assert!(matches!(
+            b.initializer(),
+            Some(Expression::IntegerLiteral(_))
+        ));
+        assert!(c.initializer().is_none());
+    }
+
+    #[test]
+    fn test_wildcard_positions() {
+        let (parser, result) =
+            apply_rule!(ParseContext::type_declaration, "class A extends List<?> {}");
+        let class = match result.expect("class must parse") {
+            TypeDeclaration::Class(class) => class,
+            ty => panic!("expected a class, got {:?}", ty),
+        };
+        let superclass = class.extends().expect("class must have a superclass");
+        assert_eq!(parser.resolve_spanned(superclass), Some("List<?>"));
+        assert!(matches!(
+            superclass.type_arguments(),
+            [TypeArgument::Wildcard(wildcard)] if wildcard.bound().is_none()
+        ));
+
+        let (parser, result) = apply_rule!(ParseContext::r#type, "Map<? extends K, ? super V>");
+        let ty = result.expect("type must parse");
+        let bounds = ty
+            .type_arguments()
+            .iter()
+            .map(|argument| match argument {
+                TypeArgument::Wildcard(wildcard) => wildcard.bound(),
+                TypeArgument::Type(ty) => panic!("expected a wildcard, got {:?}", ty),
+            })
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            bounds.as_slice(),
+            [
+                Some(WildcardBound::Extends(_)),
+                Some(WildcardBound::Super(_))
+            ]
+        ));
+        assert_eq!(
+            parser.resolve_spanned(&ty.type_arguments()[1]),
+            Some("? super V")
+        );
+
+        let (_, result) = apply_rule!(ParseContext::type_declaration, "class A<T extends ?> {}");
+        assert!(matches!(result, Err(Error::MisplacedWildcard { .. })));
+    }
+
+    #[test]
+    fn test_generic_types() {
+        let (parser, result) = apply_rule!(ParseContext::r#type, "List<String>");
+        let ty = result.expect("type must parse");
+        assert_eq!(parser.resolve_spanned(&ty), Some("List<String>"));
+        let [TypeArgument::Type(argument)] = ty.type_arguments() else {
+            panic!("expected a single type argument, got {:?}", ty);
+        };
+        assert_eq!(parser.resolve_spanned(argument), Some("String"));
+
+        let (parser, result) = apply_rule!(ParseContext::r#type, "Map<K, List<V>>");
+        let ty = result.expect("type must parse");
+        assert_eq!(parser.resolve_spanned(&ty), Some("Map<K, List<V>>"));
+        let [_, TypeArgument::Type(list)] = ty.type_arguments() else {
+            panic!("expected two type arguments, got {:?}", ty);
+        };
+        assert_eq!(parser.resolve_spanned(list), Some("List<V>"));
+
+        let (parser, result) = apply_rule!(ParseContext::r#type, "A<B<C<D>>>[]");
+        let ty = result.expect("type must parse");
+        assert_eq!(ty.dimensions(), 1);
+        let TypeArgument::Type(b) = &ty.type_arguments()[0] else {
+            panic!("expected a type argument, got {:?}", ty);
+        };
+        assert_eq!(parser.resolve_spanned(b), Some("B<C<D>>"));
+        let TypeArgument::Type(c) = &b.type_arguments()[0] else {
+            panic!("expected a type argument, got {:?}", b);
+        };
+        assert_eq!(parser.resolve_spanned(c), Some("C<D>"));
+
+        let (parser, result) = apply_rule!(
+            ParseContext::statement,
+            "Map<String, List<Integer>> m = null;"
+        );
+        let statement = result.expect("statement must parse");
+        let StatementKind::LocalVariableDeclaration(declaration) = statement.kind() else {
+            panic!("expected a local variable declaration, got {:?}", statement);
+        };
         assert_eq!(
+            parser.resolve_spanned(declaration.ty()),
+            Some("Map<String, List<Integer>>")
+        );
+    }
+
+    #[test]
+    fn test_array_type() {
+        let (parser, result) = apply_rule!(ParseContext::r#type, "List<String>[][]");
+        let ty = result.expect("type must parse");
+        assert_eq!(ty.dimensions(), 2);
+        assert_eq!(parser.resolve_spanned(&ty), Some("List<String>[][]"));
+
+        let (_, result) = apply_rule!(ParseContext::r#type, "int");
+        assert!(!result.expect("type must parse").is_array());
+    }
+
+    #[test]
+    fn test_do_while_statement() {
+        let (parser, result) =
+            apply_rule!(ParseContext::statement, "do { i = i + 1; } while (i < 10);");
+        let statement = result.expect("statement must parse");
+        let StatementKind::DoWhile(do_while) = statement.kind() else {
+            panic!("expected a do-while statement, got {:?}", statement);
+        };
+        assert_eq!(do_while.block().statements().len(), 1);
+        let Expression::Binary(condition) = do_while.condition() else {
+            panic!(
+                "expected a binary condition, got {:?}",
+                do_while.condition()
+            );
+        };
+        assert_eq!(parser.resolve_span(condition.operator()), Some("<"));
+
+        let (_, result) = apply_rule!(ParseContext::statement, "do {} while true;");
+        let Err(Error::UnexpectedToken { expected, found }) = result else {
+            panic!("expected an unexpected token, got {:?}", result);
+        };
+        assert_eq!(expected, &["("]);
+        assert_eq!(found.map(|t| *t.span()), Some(Span::new(12, 16)));
+
+        let (_, result) = apply_rule!(ParseContext::statement, "do {} while (true)");
+        assert!(matches!(
             result,
             Err(Error::UnexpectedToken {
-                expected: &["identifier"],
-                found: None,
+                expected: [";"],
+                found: None
             })
-        );
+        ));
     }
 
     #[test]
-    fn test_incomplete_qualified_name() {
-        let (_, result) = apply_rule!(ParseContext::qualified_name, "a.b.;");
+    fn test_break_and_continue() {
+        let (_, result) = apply_rule!(ParseContext::statement, "break;");
+        let statement = result.expect("statement must parse");
+        let StatementKind::Break(statement) = statement.kind() else {
+            panic!("expected a break statement, got {:?}", statement);
+        };
+        assert!(statement.label().is_none());
+
+        let (parser, result) = apply_rule!(ParseContext::statement, "break outer;");
+        let statement = result.expect("statement must parse");
+        let StatementKind::Break(statement) = statement.kind() else {
+            panic!("expected a break statement, got {:?}", statement);
+        };
+        assert_eq!(
+            statement
+                .label()
+                .and_then(|label| parser.resolve_spanned(label)),
+            Some("outer")
+        );
+
+        let (_, result) = apply_rule!(ParseContext::statement, "continue;");
+        let statement = result.expect("statement must parse");
+        let StatementKind::Continue(statement) = statement.kind() else {
+            panic!("expected a continue statement, got {:?}", statement);
+        };
+        assert!(statement.label().is_none());
+
+        let (parser, result) = apply_rule!(ParseContext::statement, "continue outer;");
+        let statement = result.expect("statement must parse");
+        let StatementKind::Continue(statement) = statement.kind() else {
+            panic!("expected a continue statement, got {:?}", statement);
+        };
         assert_eq!(
+            statement
+                .label()
+                .and_then(|label| parser.resolve_spanned(label)),
+            Some("outer")
+        );
+
+        let (_, result) = apply_rule!(ParseContext::statement, "break outer }");
+        assert!(matches!(
             result,
             Err(Error::UnexpectedToken {
-                expected: &["identifier"],
-                found: Some(Token::Separator(Separator::Semicolon(Span::new(4, 5)))),
+                expected: [";"],
+                ..
             })
+        ));
+    }
+
+    #[test]
+    fn test_if_statement() {
+        let (parser, result) = apply_rule!(
+            ParseContext::statement,
+            "if (a) f(); else if (b) { g(); } else h();"
+        );
+        let statement = match result.expect("statement must parse").kind() {
+            StatementKind::If(statement) => statement.clone(),
+            kind => panic!("expected an if statement, got {:?}", kind),
+        };
+        assert_eq!(statement.then_statement().statements().len(), 1);
+        let [else_if] = statement.else_ifs() else {
+            panic!("expected a single else-if, got {:?}", statement.else_ifs());
+        };
+        assert_eq!(
+            parser.resolve_spanned(else_if.statement()),
+            Some("{ g(); }")
         );
+        assert!(statement.else_statement().is_some());
     }
 
     #[test]
-    fn test_qualified_names() {
-        for (input, expected) in &[
-            ("a.b.c", QualifiedName::from(vec![(0, 1), (2, 3), (4, 5)])),
-            ("a.b.*", QualifiedName::from(vec![(0, 1), (2, 3), (4, 5)])),
-            (
-                "a .b . c",
-                QualifiedName::from(vec![(0, 1), (3, 4), (7, 8)]),
-            ),
-            (
-                "a.b.c hello world",
-                QualifiedName::from(vec![(0, 1), (2, 3), (4, 5)]),
-            ),
-            (
-                "hello.world.Foobar",
-                QualifiedName::from(vec![(0, 5), (6, 11), (12, 18)]),
-            ),
-        ] {
-            let (_, output) = apply_rule!(ParseContext::qualified_name, *input);
-            assert_eq!(output.unwrap(), *expected);
+    fn test_long_else_if_chain() {
+        let mut input = String::from("if (a) {}");
+        for _ in 0..1000 {
+            input.push_str(" else if (b) {}");
         }
+        input.push_str(" else {}");
+
+        let (_, result) = apply_rule!(ParseContext::statement, input.as_str());
+        let statement = match result.expect("statement must parse").kind() {
+            StatementKind::If(statement) => statement.clone(),
+            kind => panic!("expected an if statement, got {:?}", kind),
+        };
+        assert_eq!(statement.else_ifs().len(), 1000);
+        assert!(statement.else_statement().is_some());
     }
 
     #[test]
-    fn test_qualified_name_not_consume_after() {
-        let lexer = Lexer::from("a.b.c;");
-        let parser = Parser::from(lexer);
-        let tokens = parser.tokens();
-        let mut ctx = ParseContext::new(&parser, CompilationUnit::new(), tokens);
-        let qualified_name = ctx.qualified_name().unwrap();
+    fn test_method_parameters() {
+        let (parser, result) = apply_rule!(
+            ParseContext::class_member,
+            "void f(int a, final String b) {}"
+        );
+        let method = match result.expect("method must parse") {
+            ClassMember::Method(method) => method,
+            member => panic!("expected a method, got {:?}", member),
+        };
+        let parameters = method
+            .parameters()
+            .iter()
+            .map(|parameter| {
+                (
+                    parameter.modifiers().clone(),
+                    parser.resolve_spanned(parameter.parameter_type()),
+                    parser.resolve_spanned(parameter.name()),
+                )
+            })
+            .collect::<Vec<_>>();
         assert_eq!(
-            qualified_name,
-            QualifiedName::from(vec![Span::new(0, 1), Span::new(2, 3), Span::new(4, 5)]),
+            parameters,
+            vec![
+                (ParameterModifiers::empty(), Some("int"), Some("a")),
+                (ParameterModifiers::Final, Some("String"), Some("b")),
+            ]
         );
 
-        // ParseContext::qualified_name must not consume the token after the qualified name
+        let (_, result) = apply_rule!(ParseContext::class_member, "void f() {}");
+        let method = match result.expect("method must parse") {
+            ClassMember::Method(method) => method,
+            member => panic!("expected a method, got {:?}", member),
+        };
+        assert!(method.parameters().is_empty());
+    }
+
+    #[test]
+    fn test_array_creation_with_initializer() {
+        let (parser, result) = apply_rule!(ParseContext::expression, "new int[]{1,2,3}");
+        let Expression::ArrayCreation(creation) = result.expect("expression must parse") else {
+            panic!("expected an array creation");
+        };
+        assert_eq!(parser.resolve_spanned(creation.ty()), Some("int[]"));
+        assert!(creation.dimensions().is_empty());
+        let initializer = creation
+            .initializer()
+            .expect("creation must have an initializer");
+        assert_eq!(parser.resolve_spanned(initializer), Some("{1,2,3}"));
+        assert_eq!(initializer.elements().len(), 3);
+
+        let (_, result) = apply_rule!(ParseContext::expression, "new int[][] { { 1 }, { 2, }, }");
+        let Expression::ArrayCreation(creation) = result.expect("expression must parse") else {
+            panic!("expected an array creation");
+        };
+        let initializer = creation
+            .initializer()
+            .expect("creation must have an initializer");
+        assert!(matches!(
+            initializer.elements(),
+            [ArrayElement::Initializer(_), ArrayElement::Initializer(_)]
+        ));
+    }
+
+    #[test]
+    fn test_array_creation_with_dimensions() {
+        let (parser, result) = apply_rule!(ParseContext::expression, "new int[3][]");
+        let Expression::ArrayCreation(creation) = result.expect("expression must parse") else {
+            panic!("expected an array creation");
+        };
+        assert_eq!(parser.resolve_spanned(creation.ty()), Some("int"));
+        assert_eq!(creation.dimensions().len(), 1);
+        assert_eq!(creation.extra_dimensions(), 1);
+        assert!(creation.initializer().is_none());
+
+        let (_, result) = apply_rule!(ParseContext::expression, "new int[3]{1,2,3}");
         assert_eq!(
-            ctx.tokens
-                .next()
-                .expect("expected the semicolon at the end"),
-            Token::Separator(Separator::Semicolon(Span::new(5, 6)))
+            result,
+            Err(Error::ArrayInitializerWithDimensions {
+                span: Span::new(10, 11),
+            })
+        );
+    }
+
+    #[test]
+    fn test_method_body() {
+        let (parser, result) = apply_rule!(ParseContext::class_member, "void f() {}");
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        let block = method.block().expect("method must have a body");
+        assert!(block.statements().is_empty());
+        assert_eq!(parser.resolve_spanned(block), Some("{}"));
+
+        let (_, result) = apply_rule!(ParseContext::class_member, "void f() { a(); b = c; }");
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        let block = method.block().expect("method must have a body");
+        assert_eq!(block.statements().len(), 2);
+
+        let (_, result) = apply_rule!(ParseContext::class_member, "void f();");
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        assert!(method.block().is_none());
+    }
+
+    #[test]
+    fn test_method_and_constructor_classification() {
+        let (parser, result) = apply_rule!(ParseContext::class_member, "int getX() {}");
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        assert_eq!(parser.resolve_spanned(method.name()), Some("getX"));
+        let return_type = method
+            .return_type()
+            .expect("method must have a return type");
+        assert_eq!(parser.resolve_spanned(return_type), Some("int"));
+
+        let (parser, result) = apply_rule!(ParseContext::class_member, "public void run() {}");
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        assert_eq!(parser.resolve_spanned(method.name()), Some("run"));
+        assert!(method.return_type().is_none());
+
+        let (parser, result) = apply_rule!(
+            ParseContext::class_member,
+            "public A(int x) throws E { y = x; }"
+        );
+        let ClassMember::Constructor(constructor) = result.expect("constructor must parse") else {
+            panic!("expected a constructor");
+        };
+        assert_eq!(parser.resolve_spanned(constructor.name()), Some("A"));
+        assert_eq!(*constructor.visibility(), Visibility::Public);
+        assert_eq!(constructor.parameters().len(), 1);
+        assert_eq!(parser.resolve_all(constructor.throws()), vec![Some("E")]);
+        assert_eq!(constructor.block().statements().len(), 1);
+    }
+
+    #[test]
+    fn test_qualified_this() {
+        let (parser, result) = apply_rule!(ParseContext::expression, "Outer.this");
+        let Expression::This(this) = result.expect("expression must parse") else {
+            panic!("expected a this expression");
+        };
+        let qualifier = this.qualifier().expect("this must be qualified");
+        assert_eq!(parser.resolve_spanned(qualifier), Some("Outer"));
+        assert_eq!(parser.resolve_spanned(&this), Some("Outer.this"));
+
+        let (parser, result) = apply_rule!(ParseContext::expression, "this.x = a.b.this.y");
+        let Expression::Assignment(assignment) = result.expect("expression must parse") else {
+            panic!("expected an assignment");
+        };
+        let Expression::FieldAccess(target) = assignment.target() else {
+            panic!("expected a field access, got {:?}", assignment.target());
+        };
+        assert!(matches!(target.receiver(), Expression::This(this) if this.qualifier().is_none()));
+        let Expression::FieldAccess(value) = assignment.value() else {
+            panic!("expected a field access, got {:?}", assignment.value());
+        };
+        let Expression::This(this) = value.receiver() else {
+            panic!("expected a this expression, got {:?}", value.receiver());
+        };
+        assert_eq!(
+            parser.resolve_spanned(this.qualifier().unwrap()),
+            Some("a.b")
+        );
+    }
+
+    #[test]
+    fn test_wildcard_intersection_bound() {
+        let (_, result) = apply_rule!(ParseContext::r#type, "List<? extends A & B>");
+        assert_eq!(
+            result,
+            Err(Error::WildcardIntersectionBound {
+                span: Span::new(17, 18),
+            })
+        );
+
+        let (_, result) = apply_rule!(
+            ParseContext::type_declaration,
+            "class C<T extends A & B> {}"
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_method_modifiers() {
+        let (_, result) = apply_rule!(
+            ParseContext::class_member,
+            "public static final synchronized void f() {}"
+        );
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        assert_eq!(*method.visibility(), Visibility::Public);
+        assert_eq!(
+            *method.modifiers(),
+            MethodModifiers::Static | MethodModifiers::Final | MethodModifiers::Synchronized
+        );
+    }
+
+    #[test]
+    fn test_annotated_final_parameter() {
+        let (parser, result) = apply_rule!(
+            ParseContext::class_member,
+            "void f(@NotNull final String x, final @A(1) @B.C int y) {}"
+        );
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        let [x, y] = method.parameters() else {
+            panic!("expected two parameters, got {:?}", method.parameters());
+        };
+        assert_eq!(*x.modifiers(), ParameterModifiers::Final);
+        assert_eq!(parser.resolve_all(x.annotations()), vec![Some("@NotNull")]);
+        assert_eq!(
+            parser.resolve_spanned(x.annotations()[0].name()),
+            Some("NotNull")
+        );
+        assert_eq!(parser.resolve_spanned(x.parameter_type()), Some("String"));
+
+        assert_eq!(*y.modifiers(), ParameterModifiers::Final);
+        assert_eq!(
+            parser.resolve_all(y.annotations()),
+            vec![Some("@A(1)"), Some("@B.C")]
+        );
+        assert_eq!(y.annotations()[0].elements().len(), 1);
+    }
+
+    #[test]
+    fn test_annotation_elements() {
+        let (parser, result) = apply_rule!(ParseContext::annotation, r#"@A("x")"#);
+        let annotation = result.expect("annotation must parse");
+        let [element] = annotation.elements() else {
+            panic!("expected a single element, got {:?}", annotation);
+        };
+        assert!(element.is_shorthand());
+        assert!(element.name().is_none());
+        assert_eq!(element.element_name(parser.source()), Some("value"));
+        assert!(matches!(
+            element.value(),
+            ElementValue::Expression(Expression::StringLiteral(_))
+        ));
+
+        let (parser, result) = apply_rule!(ParseContext::annotation, "@A(b = 1, c = D)");
+        let annotation = result.expect("annotation must parse");
+        let names = annotation
+            .elements()
+            .iter()
+            .map(|element| element.element_name(parser.source()))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![Some("b"), Some("c")]);
+
+        let (_, result) = apply_rule!(ParseContext::annotation, "@A(b = 1, 2)");
+        assert!(matches!(
+            result,
+            Err(Error::InvalidAnnotationElement { .. })
+        ));
+        let (_, result) = apply_rule!(ParseContext::annotation, "@A(b += 1, c = 2)");
+        assert!(matches!(
+            result,
+            Err(Error::InvalidAnnotationElement { .. })
+        ));
+    }
+
+    #[test]
+    fn test_annotation_element_values() {
+        let (parser, result) = apply_rule!(
+            ParseContext::annotation,
+            "@A(x = { 1, 2, }, y = @B(3), z = {})"
+        );
+        let annotation = result.expect("annotation must parse");
+        let [x, y, z] = annotation.elements() else {
+            panic!("expected three elements, got {:?}", annotation);
+        };
+        let ElementValue::Array(array) = x.value() else {
+            panic!("expected an array, got {:?}", x.value());
+        };
+        assert_eq!(parser.resolve_spanned(array), Some("{ 1, 2, }"));
+        assert_eq!(array.values().len(), 2);
+        let ElementValue::Annotation(nested) = y.value() else {
+            panic!("expected an annotation, got {:?}", y.value());
+        };
+        assert_eq!(parser.resolve_spanned(nested), Some("@B(3)"));
+        assert!(matches!(z.value(), ElementValue::Array(array) if array.values().is_empty()));
+
+        let (_, result) = apply_rule!(ParseContext::annotation, "@A({ 1 })");
+        let annotation = result.expect("annotation must parse");
+        let [element] = annotation.elements() else {
+            panic!("expected a single element, got {:?}", annotation);
+        };
+        assert!(element.is_shorthand());
+        assert!(matches!(element.value(), ElementValue::Array(_)));
+    }
+
+    #[test]
+    fn test_array_declarations() {
+        let (parser, result) = apply_rule!(ParseContext::statement, "int[] a;");
+        let StatementKind::LocalVariableDeclaration(declaration) =
+            result.expect("statement must parse").kind().clone()
+        else {
+            panic!("expected a local variable declaration");
+        };
+        assert_eq!(parser.resolve_spanned(declaration.ty()), Some("int[]"));
+        assert_eq!(declaration.ty().dimensions(), 1);
+        assert_eq!(declaration.variables()[0].dimensions(), 0);
+
+        let (parser, result) = apply_rule!(ParseContext::class_member, "String[][] b;");
+        let ClassMember::Field(field) = result.expect("field must parse") else {
+            panic!("expected a field");
+        };
+        assert_eq!(
+            parser.resolve_spanned(field.field_type()),
+            Some("String[][]")
+        );
+        assert_eq!(field.field_type().dimensions(), 2);
+
+        let (parser, result) = apply_rule!(ParseContext::statement, "int c[];");
+        let StatementKind::LocalVariableDeclaration(declaration) =
+            result.expect("statement must parse").kind().clone()
+        else {
+            panic!("expected a local variable declaration");
+        };
+        assert_eq!(parser.resolve_spanned(declaration.ty()), Some("int"));
+        assert_eq!(declaration.variables()[0].dimensions(), 1);
+
+        let (_, result) = apply_rule!(ParseContext::class_member, "void main(String args[]) {}");
+        let ClassMember::Method(method) = result.expect("method must parse") else {
+            panic!("expected a method");
+        };
+        assert_eq!(method.parameters()[0].dimensions(), 1);
+        assert!(!method.parameters()[0].parameter_type().is_array());
+    }
+
+    #[test]
+    fn test_declaration_or_expression_statement() {
+        let (parser, result) = apply_rule!(ParseContext::statement, "List x;");
+        let StatementKind::LocalVariableDeclaration(declaration) =
+            result.expect("statement must parse").kind().clone()
+        else {
+            panic!("expected a local variable declaration");
+        };
+        assert_eq!(parser.resolve_spanned(declaration.ty()), Some("List"));
+
+        let (parser, result) = apply_rule!(
+            ParseContext::statement,
+            "java.util.Map<String, int[]>[] m = null;"
+        );
+        let StatementKind::LocalVariableDeclaration(declaration) =
+            result.expect("statement must parse").kind().clone()
+        else {
+            panic!("expected a local variable declaration");
+        };
+        assert_eq!(
+            parser.resolve_spanned(declaration.ty()),
+            Some("java.util.Map<String, int[]>[]")
         );
+
+        let (_, result) = apply_rule!(ParseContext::statement, "list.add(1);");
+        assert!(matches!(
+            result.expect("statement must parse").kind(),
+            StatementKind::Expression(Expression::MethodCall(_))
+        ));
+
+        let (_, result) = apply_rule!(ParseContext::statement, "a = b < c;");
+        assert!(matches!(
+            result.expect("statement must parse").kind(),
+            StatementKind::Expression(Expression::Assignment(_))
+        ));
     }
 }
@@ -1,9 +1,11 @@
-use crate::lexer::span::Span;
-use crate::lexer::token::Token;
+use crate::lexer::span::{Span, Spanned};
+use crate::lexer::token::{Keyword, Token};
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
+    #[error(transparent)]
+    Lexer(#[from] crate::lexer::error::Error),
     #[error("unexpected token: got {found:?} but want one of {expected:?}")]
     UnexpectedToken {
         found: Option<Token>,
@@ -13,4 +15,183 @@ pub enum Error {
     UnexpectedEOF { expected: &'static [&'static str] },
     #[error("not implemented yet")]
     NotImplemented(Option<Span>),
+    // the variant names of keywords are their text in title case
+    #[error("cannot use keyword '{}' as an identifier", .keyword.as_str().to_lowercase())]
+    KeywordAsIdentifier { keyword: Keyword },
+    #[error("'{}' is a reserved keyword and cannot be used", .keyword.as_str().to_lowercase())]
+    ReservedKeyword { keyword: Keyword },
+    #[error("'{}' conflicts with an earlier visibility modifier", .keyword.as_str().to_lowercase())]
+    ConflictingModifiers { keyword: Keyword },
+    #[error("repeated modifier '{}'", .keyword.as_str().to_lowercase())]
+    RepeatedModifier { keyword: Keyword },
+    #[error("primitive type '{}' cannot be used as a type argument", .keyword.as_str().to_lowercase())]
+    PrimitiveTypeArgument { keyword: Keyword },
+    #[error("not a statement, only assignments, increments, decrements, method calls and object creations can be used as statements")]
+    InvalidExpressionStatement { span: Span },
+    #[error("local variable is already defined in this or an enclosing block")]
+    DuplicateLocalVariable {
+        /// The span of the name of the duplicate declaration.
+        span: Span,
+    },
+    #[error("integer literal is out of range for its type")]
+    IntegerLiteralOutOfRange { span: Span },
+    #[error("an array creation cannot have both dimension expressions and an initializer")]
+    ArrayInitializerWithDimensions {
+        /// The span of the opening `{` of the initializer.
+        span: Span,
+    },
+    #[error("a wildcard can only have a single bound")]
+    WildcardIntersectionBound {
+        /// The span of the `&` after the first bound.
+        span: Span,
+    },
+    #[error("annotation elements must be named like `name = value`, unless there is only one")]
+    InvalidAnnotationElement {
+        /// The span of the annotation.
+        span: Span,
+    },
+    #[error("a wildcard can only be used as a type argument")]
+    MisplacedWildcard { span: Span },
+    #[error("only one top-level type per file can be public")]
+    MultiplePublicTypes {
+        /// The span of the name of a public type after the first one.
+        span: Span,
+    },
+    #[error("invalid receiver parameter, it can't be final and must be named `this` in a method and like `Outer.this` in a constructor")]
+    InvalidReceiverParameter {
+        /// The span of the name of the receiver parameter, like `this`.
+        span: Span,
+    },
+    #[error("annotation element is already set")]
+    DuplicateAnnotationElement {
+        /// The span of the name of the element that is set again.
+        span: Span,
+    },
+    #[error("annotation element values must be constant expressions, enum constants, annotations or arrays of them")]
+    NonConstantElementValue {
+        /// The span of the first token of the value.
+        span: Span,
+    },
+    #[error("invalid member: {cause}")]
+    InvalidMember {
+        /// The span of the first token of the member.
+        span: Span,
+        cause: Box<Error>,
+    },
+}
+
+impl Error {
+    /// A stable code that identifies the kind of this error, like `E0001` for
+    /// an unexpected token. Tools can use it to filter or document errors.
+    ///
+    /// Codes are never reused or changed once assigned, so new variants get
+    /// the next free code. Lexer errors have their own codes from `E0101`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Lexer(e) => e.code(),
+            Error::UnexpectedToken { .. } => "E0001",
+            Error::UnexpectedEOF { .. } => "E0002",
+            Error::NotImplemented(_) => "E0003",
+            Error::KeywordAsIdentifier { .. } => "E0004",
+            Error::ReservedKeyword { .. } => "E0005",
+            Error::ConflictingModifiers { .. } => "E0006",
+            Error::PrimitiveTypeArgument { .. } => "E0007",
+            Error::InvalidExpressionStatement { .. } => "E0008",
+            Error::DuplicateLocalVariable { .. } => "E0009",
+            Error::IntegerLiteralOutOfRange { .. } => "E0010",
+            Error::ArrayInitializerWithDimensions { .. } => "E0011",
+            Error::WildcardIntersectionBound { .. } => "E0012",
+            Error::InvalidAnnotationElement { .. } => "E0013",
+            Error::MisplacedWildcard { .. } => "E0014",
+            Error::InvalidMember { .. } => "E0015",
+            Error::MultiplePublicTypes { .. } => "E0016",
+            Error::InvalidReceiverParameter { .. } => "E0017",
+            Error::DuplicateAnnotationElement { .. } => "E0018",
+            Error::NonConstantElementValue { .. } => "E0019",
+            Error::RepeatedModifier { .. } => "E0020",
+        }
+    }
+}
+
+impl Spanned for Error {
+    fn span(&self) -> Option<Span> {
+        match self {
+            Error::UnexpectedToken { found, .. } => found.as_ref().map(|t| *t.span()),
+            Error::Lexer(e) => e.span(),
+            Error::UnexpectedEOF { .. } => None,
+            Error::NotImplemented(span) => *span,
+            Error::KeywordAsIdentifier { keyword }
+            | Error::ReservedKeyword { keyword }
+            | Error::PrimitiveTypeArgument { keyword }
+            | Error::ConflictingModifiers { keyword }
+            | Error::RepeatedModifier { keyword } => Some(*keyword.span()),
+            Error::InvalidExpressionStatement { span }
+            | Error::DuplicateLocalVariable { span }
+            | Error::MisplacedWildcard { span }
+            | Error::InvalidAnnotationElement { span }
+            | Error::WildcardIntersectionBound { span }
+            | Error::IntegerLiteralOutOfRange { span }
+            | Error::ArrayInitializerWithDimensions { span }
+            | Error::MultiplePublicTypes { span }
+            | Error::InvalidReceiverParameter { span }
+            | Error::DuplicateAnnotationElement { span }
+            | Error::NonConstantElementValue { span }
+            | Error::InvalidMember { span, .. } => Some(*span),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::Keyword;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_codes_are_distinct() {
+        let span = Span::new(0, 1);
+        let keyword = Keyword::new_class(span);
+        let errors = [
+            Error::Lexer(crate::lexer::error::Error::IllegalEscapeSequence { span }),
+            Error::Lexer(crate::lexer::error::Error::UnterminatedString { span }),
+            Error::Lexer(crate::lexer::error::Error::UnterminatedComment { span }),
+            Error::Lexer(crate::lexer::error::Error::MalformedIntegerLiteral { span }),
+            Error::UnexpectedToken {
+                found: None,
+                expected: &[";"],
+            },
+            Error::UnexpectedEOF { expected: &[";"] },
+            Error::NotImplemented(None),
+            Error::KeywordAsIdentifier { keyword },
+            Error::ReservedKeyword { keyword },
+            Error::ConflictingModifiers { keyword },
+            Error::PrimitiveTypeArgument { keyword },
+            Error::InvalidExpressionStatement { span },
+            Error::DuplicateLocalVariable { span },
+            Error::IntegerLiteralOutOfRange { span },
+            Error::ArrayInitializerWithDimensions { span },
+            Error::WildcardIntersectionBound { span },
+            Error::InvalidAnnotationElement { span },
+            Error::MisplacedWildcard { span },
+            Error::InvalidMember {
+                span,
+                cause: Box::new(Error::NotImplemented(None)),
+            },
+            Error::MultiplePublicTypes { span },
+            Error::InvalidReceiverParameter { span },
+            Error::DuplicateAnnotationElement { span },
+            Error::NonConstantElementValue { span },
+            Error::RepeatedModifier { keyword },
+        ];
+        let codes = errors.iter().map(Error::code).collect::<BTreeSet<_>>();
+        assert_eq!(codes.len(), errors.len(), "{:?}", codes);
+        assert_eq!(
+            Error::UnexpectedToken {
+                found: None,
+                expected: &[]
+            }
+            .code(),
+            "E0001"
+        );
+    }
 }
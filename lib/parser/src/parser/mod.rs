@@ -1,21 +1,40 @@
-use core::iter::Peekable;
-
+use crate::lexer::source::{LineEnding, Source};
 use crate::lexer::span::{Span, Spanned};
-use crate::lexer::token::Token;
-use crate::lexer::Lexer;
+use crate::lexer::token::{Comment, Keyword, Separator, Token};
+use crate::lexer::{GraphemeIndex, Lexer};
 use crate::parser::context::ParseContext;
 use crate::parser::error::Error;
-use crate::parser::tree::CompilationUnit;
+use crate::parser::token_stream::TokenStream;
+use crate::parser::tree::{ClassMember, CompilationUnit};
+use std::collections::BTreeMap;
 
 mod context;
 pub mod error;
+mod token_stream;
 pub mod tree;
 
-pub type Result<'source, T> = core::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
+    literal_overflow: LiteralOverflowPolicy,
+    /// The tokens and lexer errors restored from a token cache, which are
+    /// parsed instead of lexing the input again.
+    #[cfg(feature = "token-cache")]
+    cached_tokens: Option<(Vec<Token>, Vec<crate::lexer::error::Error>)>,
+}
+
+/// Decides how the parser handles integer literals that don't fit into
+/// their type, like `2147483648` for an `int`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum LiteralOverflowPolicy {
+    /// Out-of-range literals are parsed without a diagnostic.
+    #[default]
+    Allow,
+    /// Out-of-range literals are reported as
+    /// [`Error::IntegerLiteralOutOfRange`], but still parsed.
+    Error,
 }
 
 impl<'a> From<&'a str> for Parser<'a> {
@@ -27,46 +46,222 @@ impl<'a> From<&'a str> for Parser<'a> {
 
 impl<'a> From<Lexer<'a>> for Parser<'a> {
     fn from(lexer: Lexer<'a>) -> Self {
-        Self { lexer }
+        Self {
+            lexer,
+            literal_overflow: LiteralOverflowPolicy::default(),
+            #[cfg(feature = "token-cache")]
+            cached_tokens: None,
+        }
     }
 }
 
 impl<'a> Parser<'a> {
+    pub fn with_literal_overflow_policy(mut self, policy: LiteralOverflowPolicy) -> Self {
+        self.literal_overflow = policy;
+        self
+    }
+
+    /// Makes an unterminated string literal end at the end of its line instead
+    /// of the end of the input, so that the following lines are still parsed.
+    /// This is useful in editors, where the closing quote is often still
+    /// missing while typing. The string is still reported as unterminated.
+    pub fn with_string_recovery(mut self) -> Self {
+        self.lexer = self.lexer.with_string_recovery();
+        self
+    }
+
     pub fn parse(&self) -> CompilationUnit {
-        let tokens = self.tokens();
-        let mut context = ParseContext::new(self, CompilationUnit::new(), tokens);
+        let mut context = self.context();
         context.parse();
         context.into()
     }
 
+    /// Parses the input as a sequence of class members without the enclosing
+    /// class, like `int x; void f() {}`. This is useful for tools that work
+    /// on fragments of a class body.
+    ///
+    /// Members that can't be parsed are skipped, and reported in the returned
+    /// errors together with any lexer errors.
+    pub fn parse_members(&self) -> (Vec<ClassMember>, Vec<Error>) {
+        let mut context = self.context();
+        let members = context.member_fragment();
+        let compilation_unit = CompilationUnit::from(context);
+        (members, compilation_unit.errors().to_vec())
+    }
+
+    /// Returns the dotted name of the package declaration, without parsing
+    /// the rest of the input. Only the tokens up to the end of the package
+    /// declaration are lexed.
+    ///
+    /// Returns `None` if the input doesn't start with a well-formed package
+    /// declaration.
+    pub fn quick_package(&self) -> Option<String> {
+        let mut tokens = self.tokens();
+        tokens.next_if(|t| matches!(t, Token::Keyword(Keyword::Package(_))))?;
+
+        let mut package = String::new();
+        loop {
+            match tokens.next()? {
+                Token::Ident(ident) => package.push_str(self.resolve_span(ident.span())?),
+                _ => return None,
+            }
+            match tokens.next()? {
+                Token::Separator(Separator::Dot(_)) => package.push('.'),
+                Token::Separator(Separator::Semicolon(_)) => return Some(package),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Returns the source that spans in the parsed tree refer to, which tree
+    /// methods like [`CompilationUnit::declared_throws`] resolve names in.
+    pub fn source(&'a self) -> &'a Source<'a> {
+        self.lexer.source()
+    }
+
     pub fn resolve_span(&'a self, span: Span) -> Option<&'a str> {
         self.lexer.source().resolve_span(span)
     }
 
+    /// Returns the 1-based line and column of the grapheme at the given index,
+    /// for example to report errors as `file:line:col`.
+    pub fn line_col(&self, index: GraphemeIndex) -> (usize, usize) {
+        self.lexer.source().line_col(index)
+    }
+
+    /// Like [`Parser::line_col`], but wide characters count as two columns.
+    pub fn display_line_col(&self, index: GraphemeIndex) -> (usize, usize) {
+        self.lexer.source().display_line_col(index)
+    }
+
+    /// Returns the indentation of the line that the span starts on, see
+    /// [`Source::leading_indent`].
+    pub fn leading_indent(&'a self, span: Span) -> &'a str {
+        self.lexer.source().leading_indent(span)
+    }
+
+    /// Returns the line ending that most lines of the input end with, see
+    /// [`Source::line_ending`].
+    pub fn line_ending(&self) -> LineEnding {
+        self.lexer.source().line_ending()
+    }
+
+    /// Returns the source text of the given token.
+    pub fn token_text(&'a self, token: &Token) -> Option<&'a str> {
+        self.lexer.token_text(token)
+    }
+
     pub fn resolve_spanned(&'a self, spanned: &impl Spanned) -> Option<&'a str> {
         spanned.span().and_then(|span| self.resolve_span(span))
     }
+
+    /// Resolves all given items, like the segments of a qualified name.
+    pub fn resolve_all<'s>(&'s self, items: &[impl Spanned]) -> Vec<Option<&'s str>> {
+        let parser: &'s Parser<'s> = self;
+        items
+            .iter()
+            .map(|item| parser.resolve_spanned(item))
+            .collect()
+    }
+}
+
+#[cfg(feature = "token-cache")]
+impl<'a> Parser<'a> {
+    /// Encodes the tokens of the input and the errors reported while lexing
+    /// them, so that they can be restored with [`Parser::from_token_cache`]
+    /// instead of lexing an unchanged input again.
+    pub fn to_token_cache(&self) -> Vec<u8> {
+        self.lexer.to_bytes()
+    }
+
+    /// Creates a parser for the input that parses the tokens restored from
+    /// the bytes, which must have been written by [`Parser::to_token_cache`]
+    /// for the same input. Returns `None` if the bytes are malformed or were
+    /// written by a different version of the encoding.
+    pub fn from_token_cache(input: &'a str, bytes: &[u8]) -> Option<Self> {
+        let mut parser = Self::from(input);
+        parser.cached_tokens = Some(Lexer::from_bytes(bytes)?);
+        Some(parser)
+    }
 }
 
 impl Parser<'_> {
+    /// Returns whether the block comment at the given span is a Javadoc
+    /// comment, which starts with `/**`. The empty comment `/**/` is not.
+    fn is_doc_comment(&self, span: Span) -> bool {
+        self.resolve_span(span)
+            .is_some_and(|text| text.starts_with("/**") && text != "/**/")
+    }
+
+    /// Lexes the whole input and returns a context to parse its tokens. Lexer
+    /// errors are already added to the compilation unit of the context.
+    fn context(&self) -> ParseContext<'_, std::vec::IntoIter<Token>> {
+        let (lexed, errors) = self.lex();
+        let mut tokens = vec![];
+        // comments are not passed to the parse context, but Javadoc comments
+        // are recorded with the token that follows them, so that they can be
+        // attached to the declaration starting with that token
+        let mut doc_comments = BTreeMap::new();
+        let mut doc_comment = None;
+        for token in lexed {
+            match token {
+                Token::Comment(Comment::Block(span)) if self.is_doc_comment(span) => {
+                    doc_comment = Some(span);
+                }
+                Token::Comment(_) => {}
+                token => {
+                    if let Some(span) = doc_comment.take() {
+                        doc_comments.insert(token.span().start(), span);
+                    }
+                    tokens.push(token);
+                }
+            }
+        }
+        let mut compilation_unit = CompilationUnit::new();
+        for error in errors {
+            compilation_unit.add_error(error.into());
+        }
+
+        let tokens = TokenStream::new(tokens.into_iter());
+        let mut context = ParseContext::new(self.lexer.source(), compilation_unit, tokens);
+        context.set_doc_comments(doc_comments);
+        context.set_literal_overflow_policy(self.literal_overflow);
+        context
+    }
+
+    /// Returns all tokens of the input together with the errors reported while
+    /// lexing them, which are restored from the token cache if there is one.
+    fn lex(&self) -> (Vec<Token>, Vec<crate::lexer::error::Error>) {
+        #[cfg(feature = "token-cache")]
+        if let Some((tokens, errors)) = &self.cached_tokens {
+            return (tokens.clone(), errors.clone());
+        }
+        let mut lexed = self.lexer.tokens();
+        let tokens = lexed.by_ref().collect();
+        (tokens, lexed.errors().to_vec())
+    }
+
     /// Returns the token iterator that this parser will use.
     ///
     /// The result will not yield any comment tokens.
-    fn tokens(&self) -> Peekable<impl Iterator<Item = Token> + '_> {
-        self.lexer
-            .tokens()
-            .filter(|t| !matches!(t, Token::Comment(_)))
-            .peekable()
+    fn tokens(&self) -> TokenStream<impl Iterator<Item = Token> + '_> {
+        TokenStream::new(
+            self.lexer
+                .tokens()
+                .filter(|t| !matches!(t, Token::Comment(_))),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::lexer::span::Span;
-    use crate::lexer::token::Separator;
     use crate::lexer::Lexer;
     use crate::parser::tree::QualifiedName;
-    use crate::ImportDeclaration;
+    use crate::{
+        AnnotationMember, ClassMember, ClassModifiers, ElementValue, Expression, ImportDeclaration,
+        InterfaceMember, MethodModifiers, Node, StatementKind, TypeDeclaration, Visibility,
+    };
 
     use super::*;
 
@@ -80,6 +275,377 @@ mod tests {
         }};
     }
 
+    #[test]
+    fn test_lexer_errors_are_reported() {
+        let (_, tree) = parse!(r#"class A { void a() { f("bad\q"); } }"#);
+        assert_eq!(
+            tree.errors(),
+            &[Error::Lexer(
+                crate::lexer::error::Error::IllegalEscapeSequence {
+                    span: Span::new(27, 29)
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_declaration_counts() {
+        let (_, tree) = parse!(
+            r#"
+import java.util.List;
+
+class A {
+    String name = "a";
+
+    void a() {}
+
+    int b() {}
+}
+"#
+        );
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        assert_eq!(tree.import_count(), 1);
+        assert_eq!(tree.type_count(), 1);
+        assert_eq!(tree.method_count(), 2);
+        assert_eq!(tree.field_count(), 1);
+
+        let (_, tree) = parse!("class A { int a; interface B { void b(); } } enum C { D; }");
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        assert_eq!(tree.import_count(), 0);
+        assert_eq!(tree.type_count(), 3);
+        assert_eq!(tree.method_count(), 1);
+        assert_eq!(tree.field_count(), 1);
+    }
+
+    #[test]
+    fn test_doc_comment_is_attached() {
+        let (parser, tree) = parse!(
+            r#"
+/** A class. */
+public class A {
+    /* not a doc comment */
+    int a;
+
+    /**
+     * A method.
+     */
+    // a line comment in between
+    void b() {}
+}
+"#
+        );
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        let class = match &tree.types()[0] {
+            TypeDeclaration::Class(class) => class,
+            ty => panic!("expected a class, got {:?}", ty),
+        };
+        let doc_comment = class.doc_comment().expect("class must have a doc comment");
+        assert_eq!(parser.resolve_span(doc_comment), Some("/** A class. */"));
+
+        match &class.members()[0] {
+            ClassMember::Field(field) => assert_eq!(field.doc_comment(), None),
+            member => panic!("expected a field, got {:?}", member),
+        }
+        match &class.members()[1] {
+            ClassMember::Method(method) => {
+                let doc_comment = method
+                    .doc_comment()
+                    .expect("method must have a doc comment");
+                assert_eq!(
+                    parser.resolve_span(doc_comment),
+                    Some("/**\n     * A method.\n     */")
+                );
+            }
+            member => panic!("expected a method, got {:?}", member),
+        }
+    }
+
+    #[test]
+    fn test_token_text() {
+        let parser = Parser::from(r#"class A { String s = "s"; }"#);
+        let tokens = parser.tokens().collect::<Vec<_>>();
+        assert_eq!(parser.token_text(&tokens[0]), Some("class"));
+        assert_eq!(parser.token_text(&tokens[1]), Some("A"));
+        assert_eq!(parser.token_text(&tokens[6]), Some(r#""s""#));
+    }
+
+    #[test]
+    fn test_resolve_all() {
+        let (parser, tree) = parse!("package a.b.c;");
+        let package = tree.package().expect("package must parse");
+        assert_eq!(
+            parser.resolve_all(package.segments()),
+            vec![Some("a"), Some("b"), Some("c")]
+        );
+    }
+
+    #[test]
+    fn test_declared_throws() {
+        let (parser, tree) = parse!(
+            r#"
+class A {
+    void read() throws java.io.IOException {}
+
+    void parse() throws java.text.ParseException {}
+}
+"#
+        );
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        assert_eq!(
+            tree.declared_throws(parser.source()),
+            vec!["java.io.IOException", "java.text.ParseException"]
+        );
+    }
+
+    #[test]
+    fn test_preorder() {
+        let (_, tree) = parse!(
+            r#"
+class A {
+    int x = 1;
+
+    void f() {
+        int y = x + 2;
+        if (y > 0) g(y);
+    }
+}
+"#
+        );
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        let nodes = tree.preorder().collect::<Vec<_>>();
+        assert_eq!(nodes.len(), 15);
+        assert!(matches!(
+            nodes[..5],
+            [
+                Node::Type(_),
+                Node::Field(_),
+                Node::Expression(Expression::IntegerLiteral(_)),
+                Node::Method(_),
+                Node::Statement(_),
+            ]
+        ));
+        let expressions = nodes
+            .iter()
+            .filter(|node| matches!(node, Node::Expression(_)))
+            .count();
+        assert_eq!(expressions, 9);
+    }
+
+    #[test]
+    #[cfg(feature = "token-cache")]
+    fn test_token_cache() {
+        let input = r#"class A { String s = "\q"; void f() { x = 1; } }"#;
+        let parser = Parser::from(input);
+        let bytes = parser.to_token_cache();
+        let cached = Parser::from_token_cache(input, &bytes).expect("cache must decode");
+        let tree = cached.parse();
+        assert_eq!(tree, parser.parse());
+        // the lexer error is restored from the cache as well
+        assert!(matches!(
+            tree.errors(),
+            [Error::Lexer(
+                crate::lexer::error::Error::IllegalEscapeSequence { .. }
+            )]
+        ));
+
+        assert!(Parser::from_token_cache(input, &bytes[..bytes.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn test_parse_members() {
+        let parser = Parser::from("int x; void f() {}");
+        let (members, errors) = parser.parse_members();
+        assert!(errors.is_empty(), "{:?}", errors);
+        let [ClassMember::Field(field), ClassMember::Method(method)] = members.as_slice() else {
+            panic!("expected a field and a method, got {:?}", members);
+        };
+        assert_eq!(
+            parser.resolve_spanned(field.variables()[0].name()),
+            Some("x")
+        );
+        assert_eq!(parser.resolve_spanned(method.name()), Some("f"));
+
+        let parser = Parser::from("int; } void g() {}");
+        let (members, errors) = parser.parse_members();
+        assert!(matches!(members.as_slice(), [ClassMember::Method(_)]));
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                Error::InvalidMember { .. },
+                Error::UnexpectedToken {
+                    expected: ["member"],
+                    ..
+                }
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_unknown_token_is_reported() {
+        let (_, tree) = parse!("class A { # void a() {} }");
+        assert_eq!(tree.errors().len(), 1, "{:?}", tree.errors());
+        assert_eq!(tree.errors()[0].span(), Some(Span::new(10, 11)));
+    }
+
+    #[test]
+    fn test_error_line_col() {
+        let (parser, tree) = parse!("class A {\n    void a() {\n        #\n    }\n}");
+        let span = tree.errors()[0].span().expect("error must have a span");
+        assert_eq!(parser.line_col(span.start()), (3, 9));
+    }
+
+    #[test]
+    fn test_leading_indent() {
+        let (parser, tree) = parse!("class A {\n\tvoid a() {}\n}");
+        let TypeDeclaration::Class(class) = &tree.types()[0] else {
+            panic!("expected a class");
+        };
+        assert_eq!(parser.leading_indent(*class.name().span()), "");
+        let ClassMember::Method(method) = &class.members()[0] else {
+            panic!("expected a method");
+        };
+        let span = *method.name().span();
+        assert_eq!(parser.leading_indent(span), "\t");
+    }
+
+    #[test]
+    fn test_line_ending() {
+        let (parser, _) = parse!("class A {\r\n    int a;\r\n}\n");
+        assert_eq!(parser.line_ending(), LineEnding::CrLf);
+    }
+
+    #[test]
+    fn test_error_summary() {
+        // lexer errors are reported before parser errors, but the summary
+        // is ordered by position
+        let (parser, tree) = parse!("class A {\n    goto a;\n    String s = \"\\q\";\n}");
+        assert_eq!(tree.errors().len(), 2);
+        let lexer_error = &tree.errors()[0];
+        let keyword_error = match &tree.errors()[1] {
+            Error::InvalidMember { cause, .. } => cause,
+            e => panic!("expected an invalid member, got {:?}", e),
+        };
+        assert_eq!(
+            tree.error_summary(parser.source()),
+            format!(
+                "2:5: invalid member: {}\n3:17: {}",
+                keyword_error, lexer_error
+            )
+        );
+    }
+
+    #[test]
+    fn test_primitive_type_argument() {
+        let (parser, tree) = parse!("class A { List<int> a; List<Integer> b; List<int[]> c; }");
+        assert_eq!(tree.errors().len(), 1, "{:?}", tree.errors());
+        let keyword = match &tree.errors()[0] {
+            Error::PrimitiveTypeArgument { keyword } => keyword,
+            e => panic!("expected a primitive type argument error, got {:?}", e),
+        };
+        assert_eq!(parser.resolve_span(*keyword.span()), Some("int"));
+    }
+
+    #[test]
+    fn test_duplicate_local_variable() {
+        let (_, tree) = parse!("class A { void a() { int x; int x; } }");
+        assert_eq!(
+            tree.errors(),
+            &[Error::DuplicateLocalVariable {
+                span: Span::new(32, 33)
+            }]
+        );
+
+        // a nested block may not redeclare a variable of an enclosing block
+        let (parser, tree) = parse!("class A { void a() { int x, y; { int y; } int z; } }");
+        let [Error::DuplicateLocalVariable { span }] = tree.errors() else {
+            panic!(
+                "expected a duplicate local variable, got {:?}",
+                tree.errors()
+            );
+        };
+        assert_eq!(parser.resolve_span(*span), Some("y"));
+
+        // the scope of a variable ends with its block, and the members of an
+        // anonymous class have scopes of their own
+        let (_, tree) = parse!(
+            r#"
+class A {
+    void a() {
+        { int y; }
+        int y;
+        switch (y) { case 1: int z; }
+        int z;
+        new B() { void b() { int y; } };
+    }
+}
+"#
+        );
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+    }
+
+    #[test]
+    fn test_synchronized_modifier_and_statement() {
+        let (_, tree) = parse!(
+            r#"
+class A {
+    synchronized void f() {
+        synchronized (lock) {
+            assert ready : "not ready";
+        }
+    }
+}
+"#
+        );
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        let TypeDeclaration::Class(class) = &tree.types()[0] else {
+            panic!("expected a class");
+        };
+        let ClassMember::Method(method) = &class.members()[0] else {
+            panic!("expected a method");
+        };
+        assert_eq!(*method.modifiers(), MethodModifiers::Synchronized);
+
+        let statements = method
+            .block()
+            .expect("method must have a body")
+            .statements();
+        let StatementKind::Synchronized(synchronized) = statements[0].kind() else {
+            panic!("expected a synchronized statement, got {:?}", statements[0]);
+        };
+        let StatementKind::Assert(assert) = synchronized.block().statements()[0].kind() else {
+            panic!("expected an assert statement");
+        };
+        assert!(assert.detail().is_some());
+    }
+
+    #[test]
+    fn test_invalid_expression_statement() {
+        let (parser, tree) =
+            parse!("class A { void f() { a + b; a = b; a += f(); new A(); i++; --i; !a; } }");
+        let spans = tree
+            .errors()
+            .iter()
+            .map(|e| match e {
+                Error::InvalidExpressionStatement { span } => parser.resolve_span(*span),
+                e => panic!("expected an invalid expression statement, got {:?}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![Some("a + b;"), Some("!a;")]);
+    }
+
+    #[test]
+    fn test_merged_error_ranges() {
+        let mut tree = CompilationUnit::new();
+        for (start, end) in [(4, 5), (2, 3), (3, 4), (7, 9), (8, 10)] {
+            tree.add_error(Error::NotImplemented(Some(Span::new(start, end))));
+        }
+        tree.add_error(Error::UnexpectedEOF { expected: &["}"] });
+        assert_eq!(
+            tree.merged_error_ranges(),
+            vec![Span::new(2, 5), Span::new(7, 10)]
+        );
+    }
+
     #[test]
     fn test_erroneous_package_decl() {
         /*
@@ -184,8 +750,495 @@ public class Main {
             ]))]
         );
 
-        // TODO: assert the rest of the tree
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        let class = match tree.types() {
+            [TypeDeclaration::Class(class)] => class,
+            types => panic!("expected a single class, got {:?}", types),
+        };
+        assert_eq!(parser.resolve_spanned(class.name()), Some("Main"));
+
+        let main = match class.members() {
+            [ClassMember::Method(method)] => method,
+            members => panic!("expected a single method, got {:?}", members),
+        };
+        assert_eq!(parser.resolve_spanned(main.name()), Some("main"));
+        assert_eq!(main.visibility(), &Visibility::Public);
+        assert_eq!(main.modifiers(), &MethodModifiers::Static);
+        assert!(main.return_type().is_none());
+
+        let args = match main.parameters() {
+            [parameter] => parameter,
+            parameters => panic!("expected a single parameter, got {:?}", parameters),
+        };
+        assert_eq!(parser.resolve_spanned(args.name()), Some("args"));
+        assert_eq!(
+            parser.resolve_spanned(args.parameter_type()),
+            Some("String[]")
+        );
+        assert_eq!(args.parameter_type().dimensions(), 1);
+
+        let body = main.block().expect("main must have a body");
+        let call = match body.statements() {
+            [statement] => match statement.kind() {
+                StatementKind::Expression(Expression::MethodCall(call)) => call,
+                kind => panic!("expected a method call, got {:?}", kind),
+            },
+            statements => panic!("expected a single statement, got {:?}", statements),
+        };
+        assert!(call.receiver().is_none());
+        assert_eq!(
+            parser.resolve_spanned(call.name()),
+            Some("System.out.println")
+        );
+        let argument = match call.arguments() {
+            [Expression::StringLiteral(literal)] => literal,
+            arguments => panic!("expected a single string literal, got {:?}", arguments),
+        };
+        assert_eq!(parser.resolve_spanned(argument), Some("\"Hello, World!\""));
+    }
+    #[test]
+    fn test_invalid_member_points_to_member_start() {
+        let (_, tree) = parse!("class A {\n    public ;\n}");
+        assert_eq!(
+            tree.errors(),
+            &[Error::InvalidMember {
+                span: Span::new(14, 20),
+                cause: Box::new(Error::UnexpectedToken {
+                    expected: &["identifier"],
+                    found: Some(Token::Separator(Separator::Semicolon(Span::new(21, 22)))),
+                }),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_nested_type_in_interface() {
+        let (parser, tree) = parse!("interface I { class Nested {} }");
+        assert!(!tree.has_errors());
+
+        let interface = match tree.types() {
+            [TypeDeclaration::Interface(interface)] => interface,
+            types => panic!("expected a single interface, got {:?}", types),
+        };
+        assert_eq!(Some("I"), parser.resolve_spanned(interface.name()));
+
+        let nested = match interface.members() {
+            [InterfaceMember::Type(TypeDeclaration::Class(class))] => class,
+            members => panic!("expected a single nested class, got {:?}", members),
+        };
+        assert_eq!(Some("Nested"), parser.resolve_spanned(nested.name()));
+        assert!(nested.members().is_empty());
+    }
+
+    #[test]
+    fn test_nested_type_with_modifiers() {
+        let (parser, tree) = parse!("class A { private static class B {} public interface C {} }");
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        let class = match &tree.types()[0] {
+            TypeDeclaration::Class(class) => class,
+            t => panic!("expected a class, got {:?}", t),
+        };
+        let nested = match &class.members()[0] {
+            ClassMember::Type(TypeDeclaration::Class(nested)) => nested,
+            m => panic!("expected a nested class, got {:?}", m),
+        };
+        assert_eq!(Some("B"), parser.resolve_spanned(nested.name()));
+        assert_eq!(*nested.visibility(), Visibility::Private);
+        assert_eq!(*nested.modifiers(), ClassModifiers::Static);
+        assert!(matches!(
+            &class.members()[1],
+            ClassMember::Type(TypeDeclaration::Interface(_))
+        ));
+    }
+
+    #[test]
+    fn test_method_throws() {
+        let (parser, tree) = parse!(
+            r#"
+class A {
+    void f() throws java.io.IOException {}
+    void g() throws E, java.lang.Exception;
+}
+"#
+        );
+        assert!(!tree.has_errors());
+
+        let class = match tree.types() {
+            [TypeDeclaration::Class(class)] => class,
+            types => panic!("expected a single class, got {:?}", types),
+        };
+        let throws = class
+            .members()
+            .iter()
+            .map(|member| match member {
+                ClassMember::Method(method) => method
+                    .throws()
+                    .iter()
+                    .map(|t| parser.resolve_spanned(t).unwrap())
+                    .collect::<Vec<_>>(),
+                member => panic!("expected a method, got {:?}", member),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            throws,
+            vec![
+                vec!["java.io.IOException"],
+                vec!["E", "java.lang.Exception"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quick_package() {
+        let parser = Parser::from("package a.b;\n\nclass { this is not java");
+        assert_eq!(parser.quick_package(), Some("a.b".to_string()));
+
+        for input in ["class A {}", "package a.b", "package a.;", ""] {
+            let parser = Parser::from(input);
+            assert_eq!(parser.quick_package(), None, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_keyword_as_identifier() {
+        let (_, tree) = parse!("class A { int class; }");
+        let cause = match tree.errors() {
+            [Error::InvalidMember { cause, .. }] => cause,
+            errors => panic!("expected a single invalid member, got {:?}", errors),
+        };
+        assert_eq!(
+            **cause,
+            Error::KeywordAsIdentifier {
+                keyword: Keyword::Class(Span::new(14, 19))
+            }
+        );
+        assert_eq!(
+            cause.to_string(),
+            "cannot use keyword 'class' as an identifier"
+        );
+    }
+
+    #[test]
+    fn test_reserved_keyword() {
+        let (_, tree) = parse!("class A { int goto; }");
+        let cause = match tree.errors() {
+            [Error::InvalidMember { cause, .. }] => cause,
+            errors => panic!("expected a single invalid member, got {:?}", errors),
+        };
+        assert_eq!(
+            **cause,
+            Error::ReservedKeyword {
+                keyword: Keyword::Goto(Span::new(14, 18))
+            }
+        );
+        assert_eq!(
+            cause.to_string(),
+            "'goto' is a reserved keyword and cannot be used"
+        );
+
+        let (_, tree) = parse!("class A { const a() {} }");
+        match tree.errors() {
+            [Error::InvalidMember { cause, .. }] => {
+                assert!(matches!(**cause, Error::ReservedKeyword { .. }))
+            }
+            errors => panic!("expected a single invalid member, got {:?}", errors),
+        };
+    }
+
+    #[test]
+    fn test_line_comments_are_ignored() {
+        let (_, tree) = parse!("class A { // hi\n    int x; // field\n    // void b();\n}");
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        assert_eq!(tree.field_count(), 1);
+        assert_eq!(tree.method_count(), 0);
+    }
+
+    #[test]
+    fn test_leading_trivia_before_package() {
+        for input in [
+            "// generated\npackage a;",
+            "\u{FEFF}package a;",
+            "\u{FEFF}// generated\r\n\npackage a;",
+        ] {
+            let (parser, tree) = parse!(input);
+            assert!(!tree.has_errors(), "{:?}: {:?}", input, tree.errors());
+            assert_eq!(
+                parser.resolve_spanned(tree.package().expect("package must be parsed")),
+                Some("a")
+            );
+        }
+    }
+
+    #[test]
+    fn test_literal_overflow_policy() {
+        let input = "class A { int a = 99999999999999999999; }";
+        let tree = Parser::from(input)
+            .with_literal_overflow_policy(LiteralOverflowPolicy::Error)
+            .parse();
+        assert_eq!(
+            tree.errors(),
+            &[Error::IntegerLiteralOutOfRange {
+                span: Span::new(18, 38),
+            }]
+        );
+
+        let tree = Parser::from(input).parse();
+        assert!(!tree.has_errors());
+
+        let in_range = "class A { int a = -2147483648, b = 0xFFFFFFFF, c = 0b1, d = 017; long e = 9_223_372_036_854_775_807L, f = -9223372036854775808L; }";
+        let tree = Parser::from(in_range)
+            .with_literal_overflow_policy(LiteralOverflowPolicy::Error)
+            .parse();
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+
+        // the minimum values may only be written as the direct operand of a negation
+        let out_of_range = "class A { int a = 2147483649, b = 0x1_0000_0000, c = 2147483648, d = -(2147483648); long e = 9223372036854775808L; }";
+        let tree = Parser::from(out_of_range)
+            .with_literal_overflow_policy(LiteralOverflowPolicy::Error)
+            .parse();
+        assert_eq!(tree.errors().len(), 5, "{:?}", tree.errors());
+    }
+
+    #[test]
+    fn test_string_recovery() {
+        let input = "class A {\n String s = \"abc\n ;\n int x;\n}";
+        let tree = Parser::from(input).with_string_recovery().parse();
+        assert!(matches!(
+            tree.errors(),
+            [Error::Lexer(
+                crate::lexer::error::Error::UnterminatedString { .. }
+            )]
+        ));
+        let [TypeDeclaration::Class(class)] = tree.types() else {
+            panic!("expected a single class, got {:?}", tree.types());
+        };
+        assert_eq!(class.members().len(), 2);
+
+        // without recovery, the rest of the class is part of the string
+        let tree = Parser::from(input).parse();
+        assert!(tree.errors().len() > 1, "{:?}", tree.errors());
+    }
+
+    #[test]
+    fn test_first_difference() {
+        let (_, a) = parse!("class A { int x; void a() {} }");
+        let (_, b) = parse!("class A { int x; void bb() {} }");
+        assert_eq!(a.first_difference(&a.clone()), None);
+
+        assert_eq!(
+            a.first_difference(&b).as_deref(),
+            Some("types[0].Class.members[1].Method.name.span.end: GraphemeIndex(23) != GraphemeIndex(24)")
+        );
+    }
+
+    #[test]
+    fn test_conflicting_visibility_modifiers() {
+        let (parser, tree) = parse!("public private class A {}");
+        assert_eq!(
+            tree.errors(),
+            &[Error::ConflictingModifiers {
+                keyword: Keyword::Private(Span::new(7, 14)),
+            }]
+        );
+        let class = match tree.types() {
+            [TypeDeclaration::Class(class)] => class,
+            types => panic!("expected a single class, got {:?}", types),
+        };
+        assert_eq!(parser.resolve_spanned(class.name()), Some("A"));
+        assert_eq!(class.visibility(), &Visibility::Public);
+
+        let (_, tree) = parse!("class A { protected public int x; }");
+        assert_eq!(tree.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_repeated_visibility_modifier() {
+        let (_, tree) = parse!("public public class A {}");
+        assert_eq!(
+            tree.errors(),
+            &[Error::RepeatedModifier {
+                keyword: Keyword::Public(Span::new(7, 13)),
+            }]
+        );
+        assert_eq!(tree.types()[0].visibility(), &Visibility::Public);
+    }
+
+    #[test]
+    fn test_constructor_receiver_parameter() {
+        let (parser, tree) = parse!(
+            r#"
+class Outer {
+    class Inner {
+        Inner(Outer Outer.this) {}
+        Inner(Outer this, int a) {}
+        void f(Inner Inner.this) {}
+        void g(final Inner this) {}
+    }
+}
+"#
+        );
+        let spans = tree
+            .errors()
+            .iter()
+            .map(|e| match e {
+                Error::InvalidReceiverParameter { span } => parser.resolve_span(*span),
+                e => panic!("expected an invalid receiver parameter, got {:?}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![Some("this"), Some("Inner.this"), Some("this")]);
+
+        let [TypeDeclaration::Class(outer)] = tree.types() else {
+            panic!("expected a single class, got {:?}", tree.types());
+        };
+        let [ClassMember::Type(TypeDeclaration::Class(inner))] = outer.members() else {
+            panic!("expected an inner class, got {:?}", outer.members());
+        };
+        let ClassMember::Constructor(constructor) = &inner.members()[0] else {
+            panic!("expected a constructor, got {:?}", inner.members()[0]);
+        };
+        let receiver = constructor
+            .receiver()
+            .expect("constructor must have a receiver");
+        assert_eq!(parser.resolve_spanned(receiver.ty()), Some("Outer"));
+        assert_eq!(
+            receiver.qualifier().and_then(|q| parser.resolve_spanned(q)),
+            Some("Outer")
+        );
+        assert!(constructor.parameters().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_annotation_element() {
+        let (parser, tree) = parse!("class A { void f(@A(b = 1, c = 2, b = 3) int x) {} }");
+        let [Error::DuplicateAnnotationElement { span }] = tree.errors() else {
+            panic!("expected a duplicate element, got {:?}", tree.errors());
+        };
+        assert_eq!(*span, Span::new(34, 35));
+        assert_eq!(parser.resolve_span(*span), Some("b"));
+    }
+
+    #[test]
+    fn test_constant_annotation_element_values() {
+        let (_, tree) = parse!(
+            r#"
+class C {
+    void f(
+        @A(1 < 2 && true)
+        @B(a = -1, b = (int) 2L, c = "a" + "b", d = Color.RED, e = X ? (1) : ~2, f = { (String) "s" })
+        int x
+    ) {}
+}
+"#
+        );
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+
+        let (parser, tree) =
+            parse!("class C { void f(@A(a = f(), b = new C(), c = i++, d = null, e = { 1, this }) int x) {} }");
+        let spans = tree
+            .errors()
+            .iter()
+            .map(|e| match e {
+                Error::NonConstantElementValue { span } => parser.resolve_span(*span),
+                _ => panic!("expected a non-constant element value, got {:?}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            [
+                Some("f"),
+                Some("new"),
+                Some("i"),
+                Some("null"),
+                Some("this")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_annotation_elements() {
+        let (_, tree) =
+            parse!("@interface A { int f(int x) { } void g(); int h() {} int value() default 1; }");
+        assert_eq!(tree.errors().len(), 3, "{:?}", tree.errors());
+        assert!(tree
+            .errors()
+            .iter()
+            .all(|e| matches!(e, Error::InvalidMember { .. })));
+        let [TypeDeclaration::Annotation(annotation)] = tree.types() else {
+            panic!("expected an annotation type, got {:?}", tree.types());
+        };
+        assert!(matches!(
+            annotation.members(),
+            [AnnotationMember::Element(element)] if element.default().is_some()
+        ));
+    }
+
+    #[test]
+    fn test_normalized_annotation() {
+        let input = r#"
+@interface A {
+    int[] value();
+    int b()[];
+    String name();
+}
+class B {
+    void f(@A(1) int x, @A(b = { 2 }, name = "n") int y) {}
+}
+"#;
+        let (parser, tree) = parse!(input);
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        let [TypeDeclaration::Annotation(declaration), TypeDeclaration::Class(class)] =
+            tree.types()
+        else {
+            panic!(
+                "expected an annotation type and a class, got {:?}",
+                tree.types()
+            );
+        };
+        let [ClassMember::Method(method)] = class.members() else {
+            panic!("expected a method, got {:?}", class.members());
+        };
+        let [x, y] = method.parameters() else {
+            panic!("expected two parameters, got {:?}", method.parameters());
+        };
+        let source = parser.source();
+
+        let annotation = x.annotations()[0].normalized(declaration, source);
+        let [value] = annotation.elements() else {
+            panic!("expected a single element, got {:?}", annotation);
+        };
+        assert!(value.is_shorthand());
+        let ElementValue::Array(array) = value.value() else {
+            panic!("expected an array, got {:?}", value.value());
+        };
+        assert!(matches!(
+            array.values(),
+            [ElementValue::Expression(Expression::IntegerLiteral(_))]
+        ));
+        assert_eq!(array.span(), None);
+
+        // values that already are arrays, and values of other elements, are kept
+        let annotation = y.annotations()[0].normalized(declaration, source);
+        assert_eq!(annotation, y.annotations()[0]);
+    }
+
+    #[test]
+    fn test_multiple_top_level_types() {
+        let (parser, tree) = parse!("class A {} class B {}");
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        assert!(tree.warnings().is_empty(), "{:?}", tree.warnings());
+        let names = tree
+            .types()
+            .iter()
+            .map(|ty| parser.resolve_spanned(ty.name()))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![Some("A"), Some("B")]);
 
-        println!("{:#?}", tree);
+        let (parser, tree) = parse!("public class A {} public class B {}");
+        assert!(!tree.has_errors(), "{:?}", tree.errors());
+        assert_eq!(tree.types().len(), 2);
+        let [Error::MultiplePublicTypes { span }] = tree.warnings() else {
+            panic!("expected a single warning, got {:?}", tree.warnings());
+        };
+        assert_eq!(parser.resolve_span(*span), Some("B"));
     }
 }
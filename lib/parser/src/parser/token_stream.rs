@@ -0,0 +1,69 @@
+use crate::lexer::token::Token;
+use std::collections::VecDeque;
+
+/// A token iterator that can look ahead an arbitrary number of tokens.
+///
+/// This is needed for rules that can't be decided by only looking at the
+/// next token, like telling apart a cast `(Foo) x` from a parenthesized
+/// expression `(foo)`.
+pub(in crate::parser) struct TokenStream<I>
+where
+    I: Iterator<Item = Token>,
+{
+    tokens: I,
+    lookahead: VecDeque<Token>,
+}
+
+impl<I> TokenStream<I>
+where
+    I: Iterator<Item = Token>,
+{
+    pub fn new(tokens: I) -> Self {
+        Self {
+            tokens,
+            lookahead: VecDeque::new(),
+        }
+    }
+
+    /// Returns the next token without consuming it.
+    pub fn peek(&mut self) -> Option<&Token> {
+        self.peek_nth(0)
+    }
+
+    /// Returns the `n`-th next token without consuming anything, where `0`
+    /// is the next token.
+    pub fn peek_nth(&mut self, n: usize) -> Option<&Token> {
+        while self.lookahead.len() <= n {
+            self.lookahead.push_back(self.tokens.next()?);
+        }
+        self.lookahead.get(n)
+    }
+
+    /// Puts a token back in front of the stream, so that it is the next token
+    /// returned.
+    pub fn push_front(&mut self, token: Token) {
+        self.lookahead.push_front(token);
+    }
+
+    /// Consumes and returns the next token if it satisfies the predicate.
+    pub fn next_if<F>(&mut self, f: F) -> Option<Token>
+    where
+        F: FnOnce(&Token) -> bool,
+    {
+        match self.peek() {
+            Some(token) if f(token) => self.next(),
+            _ => None,
+        }
+    }
+}
+
+impl<I> Iterator for TokenStream<I>
+where
+    I: Iterator<Item = Token>,
+{
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lookahead.pop_front().or_else(|| self.tokens.next())
+    }
+}
@@ -0,0 +1,171 @@
+use crate::lexer::source::Source;
+use crate::lexer::span::{Span, Spanned};
+use crate::{AnnotationDeclaration, AnnotationMember, Expression, Identifier, QualifiedName};
+
+/// An annotation like `@Override`, `@SuppressWarnings("unchecked")` or
+/// `@Retention(value = RUNTIME)`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Annotation {
+    name: QualifiedName,
+    elements: Vec<ElementValuePair>,
+    span: Span,
+}
+
+impl Annotation {
+    pub(in crate::parser) fn new(
+        name: QualifiedName,
+        elements: Vec<ElementValuePair>,
+        span: Span,
+    ) -> Self {
+        Self {
+            name,
+            elements,
+            span,
+        }
+    }
+
+    pub fn name(&self) -> &QualifiedName {
+        &self.name
+    }
+
+    /// The element-value pairs in parentheses after the name. A single value
+    /// without a name like in `@A("x")` is the shorthand for `@A(value = "x")`,
+    /// see [`ElementValuePair::is_shorthand`].
+    pub fn elements(&self) -> &[ElementValuePair] {
+        &self.elements
+    }
+
+    /// Returns this annotation with every single value of an array element
+    /// wrapped in a one-element array, since Java treats `@A(1)` like
+    /// `@A({1})` if the element is declared as `int[] value();`.
+    ///
+    /// The element types are looked up by name in the given declaration,
+    /// which must be the one of this annotation's type. Names are resolved in
+    /// the given source, which must contain both.
+    pub fn normalized(&self, declaration: &AnnotationDeclaration, source: &Source) -> Annotation {
+        let array_elements = declaration
+            .members()
+            .iter()
+            .filter_map(|member| match member {
+                AnnotationMember::Element(element)
+                    if element.element_type().is_array() || element.dimensions() > 0 =>
+                {
+                    source.resolve_span(*element.name().span())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut annotation = self.clone();
+        for element in &mut annotation.elements {
+            let name = element.element_name(source);
+            if !name.is_some_and(|name| array_elements.contains(&name))
+                || matches!(element.value, ElementValue::Array(_))
+            {
+                continue;
+            }
+            let value = element.value.clone();
+            element.value = ElementValue::Array(ElementValueArray::single(value));
+        }
+        annotation
+    }
+}
+
+impl Spanned for Annotation {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+/// An element-value pair like `value = RUNTIME` in an annotation.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ElementValuePair {
+    name: Option<Identifier>,
+    value: ElementValue,
+}
+
+impl ElementValuePair {
+    pub(in crate::parser) fn new(name: Identifier, value: ElementValue) -> Self {
+        Self {
+            name: Some(name),
+            value,
+        }
+    }
+
+    /// Creates the pair of the shorthand `@A("x")`, which has no name in the
+    /// source.
+    pub(in crate::parser) fn shorthand(value: ElementValue) -> Self {
+        Self { name: None, value }
+    }
+
+    /// The name of the element as written in the source, or `None` for the
+    /// shorthand `@A("x")`, see [`ElementValuePair::element_name`].
+    pub fn name(&self) -> Option<&Identifier> {
+        self.name.as_ref()
+    }
+
+    /// The name of the element that this pair sets, resolved in the given
+    /// source. This is `value` for the shorthand `@A("x")`.
+    pub fn element_name<'a>(&self, source: &'a Source<'a>) -> Option<&'a str> {
+        match &self.name {
+            Some(name) => source.resolve_span(*name.span()),
+            None => Some("value"),
+        }
+    }
+
+    /// Whether this is the shorthand `@A("x")` for `@A(value = "x")`.
+    pub fn is_shorthand(&self) -> bool {
+        self.name.is_none()
+    }
+
+    pub fn value(&self) -> &ElementValue {
+        &self.value
+    }
+}
+
+/// The value of an annotation element, like `RUNTIME` in
+/// `@Retention(RUNTIME)`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ElementValue {
+    Expression(Expression),
+    /// An array of values like `{ "a", "b" }`.
+    Array(ElementValueArray),
+    /// A nested annotation like `@B` in `@A(@B)`.
+    Annotation(Annotation),
+}
+
+/// An array of element values like `{ "a", "b" }`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ElementValueArray {
+    values: Vec<ElementValue>,
+    span: Option<Span>,
+}
+
+impl ElementValueArray {
+    pub(in crate::parser) fn new(values: Vec<ElementValue>, span: Span) -> Self {
+        Self {
+            values,
+            span: Some(span),
+        }
+    }
+
+    /// Creates the one-element array of a single value, see
+    /// [`Annotation::normalized`]. It is not written in the source, so it has
+    /// no span.
+    fn single(value: ElementValue) -> Self {
+        Self {
+            values: vec![value],
+            span: None,
+        }
+    }
+
+    pub fn values(&self) -> &[ElementValue] {
+        &self.values
+    }
+}
+
+impl Spanned for ElementValueArray {
+    fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
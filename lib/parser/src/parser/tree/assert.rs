@@ -5,3 +5,18 @@ pub struct AssertStatement {
     condition: Expression,
     detail: Option<Expression>,
 }
+
+impl AssertStatement {
+    pub(in crate::parser) fn new(condition: Expression, detail: Option<Expression>) -> Self {
+        Self { condition, detail }
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    /// The detail message, like `"message"` in `assert x : "message";`.
+    pub fn detail(&self) -> Option<&Expression> {
+        self.detail.as_ref()
+    }
+}
@@ -1,6 +1,35 @@
+use crate::lexer::span::{Span, Spanned};
 use crate::parser::tree::statement::Statement;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct Block {
     statements: Vec<Statement>,
+    span: Option<Span>,
+}
+
+impl Block {
+    pub(in crate::parser) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(in crate::parser) fn add_statement(&mut self, statement: Statement) {
+        self.statements.push(statement);
+    }
+
+    pub(in crate::parser) fn set_span(&mut self, span: Span) {
+        self.span = Some(span);
+    }
+
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
+}
+
+impl Spanned for Block {
+    /// The span from the opening to the closing curly brace. A block that
+    /// isn't written with braces, like the single statement body of a loop,
+    /// has no span.
+    fn span(&self) -> Option<Span> {
+        self.span
+    }
 }
@@ -1,16 +1,20 @@
+use crate::lexer::source::Source;
 use crate::lexer::span::{Span, Spanned};
 use crate::parser::error::Error;
+use crate::parser::tree::debug_path::{self, DebugPath};
 use crate::parser::tree::identifier::Identifier;
 use crate::parser::tree::qualified_name::QualifiedName;
 use crate::parser::tree::{
-    AnnotationModifiers, Block, ClassModifiers, EnumModifiers, Expression, FieldModifiers,
-    InterfaceModifiers, MethodModifiers, ParameterModifiers,
+    Annotation, AnnotationModifiers, Block, ClassModifiers, ElementValue, EnumModifiers,
+    Expression, FieldModifiers, InterfaceModifiers, MethodModifiers, ParameterModifiers, Preorder,
+    Type, TypeParameter,
 };
 use crate::Visibility;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CompilationUnit {
     errors: Vec<Error>,
+    warnings: Vec<Error>,
     package: Option<QualifiedName>,
     imports: Vec<ImportDeclaration>,
     types: Vec<TypeDeclaration>,
@@ -20,6 +24,7 @@ impl CompilationUnit {
     pub(in crate::parser) fn new() -> Self {
         Self {
             errors: vec![],
+            warnings: vec![],
             package: None,
             imports: vec![],
             types: vec![],
@@ -38,6 +43,93 @@ impl CompilationUnit {
         !self.errors.is_empty()
     }
 
+    pub(in crate::parser) fn add_warning(&mut self, warning: Error) {
+        self.warnings.push(warning);
+    }
+
+    /// Problems that don't prevent the tree from being built, but that a
+    /// compiler would still reject, like a second public top-level type.
+    /// Unlike [`CompilationUnit::errors`], these don't count for
+    /// [`CompilationUnit::has_errors`].
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    /// Returns the spans of all errors, where adjacent or overlapping spans
+    /// are merged into a single range. Errors without a span are omitted.
+    pub fn merged_error_ranges(&self) -> Vec<Span> {
+        let mut spans = self
+            .errors
+            .iter()
+            .filter_map(|e| e.span())
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|span| span.start());
+
+        let mut ranges: Vec<Span> = vec![];
+        for span in spans {
+            match ranges.last_mut() {
+                Some(last) if span.start() <= last.end() => {
+                    *last = Span::new(last.start(), last.end().max(span.end()));
+                }
+                _ => ranges.push(span),
+            }
+        }
+        ranges
+    }
+
+    /// Renders all errors as `line:col: message`, one error per line and
+    /// sorted by their position in the source. Errors without a position,
+    /// like an unexpected end of input, come last and have no location.
+    pub fn error_summary(&self, source: &Source) -> String {
+        let mut errors = self.errors.iter().collect::<Vec<_>>();
+        errors.sort_by_key(|e| {
+            let start = e.span().map(|span| span.start());
+            (start.is_none(), start)
+        });
+        errors
+            .into_iter()
+            .map(|error| match error.span() {
+                Some(span) => {
+                    let (line, col) = source.line_col(span.start());
+                    format!("{}:{}: {}", line, col, error)
+                }
+                None => error.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Describes the first difference between this and the other compilation
+    /// unit, or returns `None` if they are equal. This is meant for tests,
+    /// where an assertion on whole trees is hard to read.
+    ///
+    /// The description is the path to the first differing value in the
+    /// `{:#?}` output of both units, followed by both values, like
+    /// `types[0].Class.members[0].Method.name.span.end: GraphemeIndex(16) != GraphemeIndex(17)`.
+    pub fn first_difference(&self, other: &Self) -> Option<String> {
+        if self == other {
+            return None;
+        }
+
+        let left = format!("{:#?}", self);
+        let right = format!("{:#?}", other);
+        let mut path = DebugPath::default();
+        for (left_line, right_line) in left.lines().zip(right.lines()) {
+            if left_line != right_line {
+                return Some(format!(
+                    "{}: {} != {}",
+                    path.describe(left_line),
+                    debug_path::value(left_line),
+                    debug_path::value(right_line)
+                ));
+            }
+            path.push_line(left_line);
+        }
+        // unequal trees can't have equal debug output, but don't panic in a
+        // helper that is used to explain a failure
+        Some(String::from("the trees differ"))
+    }
+
     pub(in crate::parser) fn set_package(&mut self, package: QualifiedName) {
         self.package = Some(package);
     }
@@ -61,6 +153,74 @@ impl CompilationUnit {
     pub fn types(&self) -> &[TypeDeclaration] {
         &self.types
     }
+
+    /// Returns an iterator over all type declarations, members, statements
+    /// and expressions in pre-order, which is the order in which they appear
+    /// in the source.
+    pub fn preorder(&self) -> Preorder<'_> {
+        Preorder::new(&self.types)
+    }
+
+    pub fn import_count(&self) -> usize {
+        self.imports.len()
+    }
+
+    /// The number of type declarations, including nested types.
+    pub fn type_count(&self) -> usize {
+        self.declaration_counts().types
+    }
+
+    /// The number of methods in all type declarations, including nested types
+    /// and the elements of annotation types.
+    pub fn method_count(&self) -> usize {
+        self.declaration_counts().methods
+    }
+
+    /// The number of fields in all type declarations, including nested types.
+    pub fn field_count(&self) -> usize {
+        self.declaration_counts().fields
+    }
+
+    /// Returns the names of all exception types in the throws clauses of
+    /// methods and constructors, including those of nested types, in the order
+    /// in which they are declared. The names are returned as they are written,
+    /// so they are only fully qualified if the source qualifies them.
+    pub fn declared_throws(&self, source: &Source) -> Vec<String> {
+        let mut throws = vec![];
+        self.walk_declarations(|declaration| {
+            let types = match declaration {
+                Declaration::Method(method) => method.throws(),
+                Declaration::Constructor(constructor) => constructor.throws(),
+                _ => return,
+            };
+            throws.extend(types.iter().filter_map(|ty| ty.name()?.resolve(source)));
+        });
+        throws
+    }
+
+    fn declaration_counts(&self) -> DeclarationCounts {
+        let mut counts = DeclarationCounts::default();
+        self.walk_declarations(|declaration| match declaration {
+            Declaration::Type => counts.types += 1,
+            Declaration::Field(field) => counts.fields += field.variables().len(),
+            Declaration::Method(_) | Declaration::AnnotationElement => counts.methods += 1,
+            Declaration::Constructor(_) => {}
+        });
+        counts
+    }
+
+    fn walk_declarations<'a>(&'a self, mut f: impl FnMut(Declaration<'a>)) {
+        for ty in &self.types {
+            ty.walk_declarations(&mut f);
+        }
+    }
+}
+
+#[derive(Default)]
+struct DeclarationCounts {
+    types: usize,
+    methods: usize,
+    fields: usize,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -90,14 +250,105 @@ pub enum TypeDeclaration {
     Annotation(AnnotationDeclaration),
 }
 
+impl TypeDeclaration {
+    pub fn name(&self) -> &Identifier {
+        match self {
+            TypeDeclaration::Class(class) => &class.name,
+            TypeDeclaration::Interface(interface) => &interface.name,
+            TypeDeclaration::Enum(enumeration) => &enumeration.name,
+            TypeDeclaration::Annotation(annotation) => &annotation.name,
+        }
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        match self {
+            TypeDeclaration::Class(class) => &class.visibility,
+            TypeDeclaration::Interface(interface) => &interface.visibility,
+            TypeDeclaration::Enum(enumeration) => &enumeration.visibility,
+            TypeDeclaration::Annotation(annotation) => &annotation.visibility,
+        }
+    }
+
+    pub(in crate::parser) fn set_doc_comment(&mut self, doc_comment: Span) {
+        let target = match self {
+            TypeDeclaration::Class(class) => &mut class.doc_comment,
+            TypeDeclaration::Interface(interface) => &mut interface.doc_comment,
+            TypeDeclaration::Enum(enumeration) => &mut enumeration.doc_comment,
+            TypeDeclaration::Annotation(annotation) => &mut annotation.doc_comment,
+        };
+        *target = Some(doc_comment);
+    }
+
+    /// Calls `f` with this type declaration and all of its members, descending
+    /// into nested type declarations.
+    fn walk_declarations<'a>(&'a self, f: &mut impl FnMut(Declaration<'a>)) {
+        f(Declaration::Type);
+        match self {
+            TypeDeclaration::Class(class) => {
+                for member in &class.members {
+                    match member {
+                        ClassMember::Type(ty) => ty.walk_declarations(f),
+                        ClassMember::Field(field) => f(Declaration::Field(field)),
+                        ClassMember::Method(method) => f(Declaration::Method(method)),
+                        ClassMember::Constructor(constructor) => {
+                            f(Declaration::Constructor(constructor))
+                        }
+                    }
+                }
+            }
+            TypeDeclaration::Interface(interface) => {
+                for member in &interface.members {
+                    match member {
+                        InterfaceMember::Type(ty) => ty.walk_declarations(f),
+                        InterfaceMember::Method(method) => f(Declaration::Method(method)),
+                    }
+                }
+            }
+            TypeDeclaration::Enum(enumeration) => {
+                for member in &enumeration.members {
+                    match member {
+                        EnumMember::Type(ty) => ty.walk_declarations(f),
+                        EnumMember::Field(field) => f(Declaration::Field(field)),
+                        EnumMember::Method(method) => f(Declaration::Method(method)),
+                        EnumMember::Constructor(constructor) => {
+                            f(Declaration::Constructor(constructor))
+                        }
+                        EnumMember::EnumConstant(_) => {}
+                    }
+                }
+            }
+            TypeDeclaration::Annotation(annotation) => {
+                for member in &annotation.members {
+                    match member {
+                        AnnotationMember::Type(ty) => ty.walk_declarations(f),
+                        AnnotationMember::Field(field) => f(Declaration::Field(field)),
+                        AnnotationMember::Element(_) => f(Declaration::AnnotationElement),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A declaration visited by [`TypeDeclaration::walk_declarations`].
+enum Declaration<'a> {
+    Type,
+    Field(&'a FieldDeclaration),
+    Method(&'a MethodDeclaration),
+    Constructor(&'a ConstructorDeclaration),
+    AnnotationElement,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ClassDeclaration {
     visibility: Visibility,
     modifiers: ClassModifiers,
     name: Identifier,
-    extends: Option<QualifiedName>,
-    implements: Vec<QualifiedName>,
+    type_parameters: Vec<TypeParameter>,
+    extends: Option<Type>,
+    implements: Vec<Type>,
     members: Vec<ClassMember>,
+    doc_comment: Option<Span>,
 }
 
 impl ClassDeclaration {
@@ -110,15 +361,62 @@ impl ClassDeclaration {
             visibility,
             modifiers,
             name,
+            type_parameters: vec![],
             extends: None,
             implements: vec![],
             members: vec![],
+            doc_comment: None,
         }
     }
 
+    pub(in crate::parser) fn add_type_parameter(&mut self, type_parameter: TypeParameter) {
+        self.type_parameters.push(type_parameter);
+    }
+
+    pub(in crate::parser) fn set_extends(&mut self, superclass: Type) {
+        self.extends = Some(superclass);
+    }
+
+    pub(in crate::parser) fn add_implements(&mut self, interface: Type) {
+        self.implements.push(interface);
+    }
+
     pub(in crate::parser) fn add_member(&mut self, member: ClassMember) {
         self.members.push(member);
     }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn modifiers(&self) -> &ClassModifiers {
+        &self.modifiers
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    pub fn type_parameters(&self) -> &[TypeParameter] {
+        &self.type_parameters
+    }
+
+    pub fn extends(&self) -> Option<&Type> {
+        self.extends.as_ref()
+    }
+
+    pub fn implements(&self) -> &[Type] {
+        &self.implements
+    }
+
+    pub fn members(&self) -> &[ClassMember] {
+        &self.members
+    }
+
+    /// The span of the Javadoc comment directly preceding this declaration.
+    pub fn doc_comment(&self) -> Option<Span> {
+        self.doc_comment
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -126,8 +424,65 @@ pub struct InterfaceDeclaration {
     visibility: Visibility,
     modifiers: InterfaceModifiers,
     name: Identifier,
+    type_parameters: Vec<TypeParameter>,
     extends: Vec<QualifiedName>,
     members: Vec<InterfaceMember>,
+    doc_comment: Option<Span>,
+}
+
+impl InterfaceDeclaration {
+    pub(in crate::parser) fn new(
+        visibility: Visibility,
+        modifiers: InterfaceModifiers,
+        name: Identifier,
+    ) -> Self {
+        Self {
+            visibility,
+            modifiers,
+            name,
+            type_parameters: vec![],
+            extends: vec![],
+            members: vec![],
+            doc_comment: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_type_parameter(&mut self, type_parameter: TypeParameter) {
+        self.type_parameters.push(type_parameter);
+    }
+
+    pub(in crate::parser) fn add_member(&mut self, member: InterfaceMember) {
+        self.members.push(member);
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn modifiers(&self) -> &InterfaceModifiers {
+        &self.modifiers
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    pub fn type_parameters(&self) -> &[TypeParameter] {
+        &self.type_parameters
+    }
+
+    pub fn extends(&self) -> &[QualifiedName] {
+        &self.extends
+    }
+
+    pub fn members(&self) -> &[InterfaceMember] {
+        &self.members
+    }
+
+    /// The span of the Javadoc comment directly preceding this declaration.
+    pub fn doc_comment(&self) -> Option<Span> {
+        self.doc_comment
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -137,6 +492,53 @@ pub struct EnumDeclaration {
     name: Identifier,
     implements: Vec<QualifiedName>,
     members: Vec<EnumMember>,
+    doc_comment: Option<Span>,
+}
+
+impl EnumDeclaration {
+    pub(in crate::parser) fn new(
+        visibility: Visibility,
+        modifiers: EnumModifiers,
+        name: Identifier,
+    ) -> Self {
+        Self {
+            visibility,
+            modifiers,
+            name,
+            implements: vec![],
+            members: vec![],
+            doc_comment: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_member(&mut self, member: EnumMember) {
+        self.members.push(member);
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn modifiers(&self) -> &EnumModifiers {
+        &self.modifiers
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    pub fn implements(&self) -> &[QualifiedName] {
+        &self.implements
+    }
+
+    pub fn members(&self) -> &[EnumMember] {
+        &self.members
+    }
+
+    /// The span of the Javadoc comment directly preceding this declaration.
+    pub fn doc_comment(&self) -> Option<Span> {
+        self.doc_comment
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -145,6 +547,48 @@ pub struct AnnotationDeclaration {
     modifiers: AnnotationModifiers,
     name: Identifier,
     members: Vec<AnnotationMember>,
+    doc_comment: Option<Span>,
+}
+
+impl AnnotationDeclaration {
+    pub(in crate::parser) fn new(
+        visibility: Visibility,
+        modifiers: AnnotationModifiers,
+        name: Identifier,
+    ) -> Self {
+        Self {
+            visibility,
+            modifiers,
+            name,
+            members: vec![],
+            doc_comment: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_member(&mut self, member: AnnotationMember) {
+        self.members.push(member);
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn modifiers(&self) -> &AnnotationModifiers {
+        &self.modifiers
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    pub fn members(&self) -> &[AnnotationMember] {
+        &self.members
+    }
+
+    /// The span of the Javadoc comment directly preceding this declaration.
+    pub fn doc_comment(&self) -> Option<Span> {
+        self.doc_comment
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -166,6 +610,7 @@ pub enum EnumMember {
     EnumConstant(Identifier),
     Type(TypeDeclaration),
     Field(FieldDeclaration),
+    Method(MethodDeclaration),
     Constructor(ConstructorDeclaration),
 }
 
@@ -173,40 +618,479 @@ pub enum EnumMember {
 pub enum AnnotationMember {
     Type(TypeDeclaration),
     Field(FieldDeclaration),
-    Method(MethodDeclaration),
+    Element(AnnotationElement),
+}
+
+/// An element of an annotation type, like `int value() default 1;`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AnnotationElement {
+    visibility: Visibility,
+    modifiers: MethodModifiers,
+    element_type: Type,
+    name: Identifier,
+    dimensions: usize,
+    default: Option<ElementValue>,
+    doc_comment: Option<Span>,
+}
+
+impl AnnotationElement {
+    pub(in crate::parser) fn new(
+        visibility: Visibility,
+        modifiers: MethodModifiers,
+        element_type: Type,
+        name: Identifier,
+    ) -> Self {
+        Self {
+            visibility,
+            modifiers,
+            element_type,
+            name,
+            dimensions: 0,
+            default: None,
+            doc_comment: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_dimension(&mut self) {
+        self.dimensions += 1;
+    }
+
+    pub(in crate::parser) fn set_default(&mut self, default: ElementValue) {
+        self.default = Some(default);
+    }
+
+    pub(in crate::parser) fn set_doc_comment(&mut self, doc_comment: Span) {
+        self.doc_comment = Some(doc_comment);
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn modifiers(&self) -> &MethodModifiers {
+        &self.modifiers
+    }
+
+    /// The type of the element. Array brackets after the parentheses, like
+    /// in `int value()[]`, are not part of it.
+    pub fn element_type(&self) -> &Type {
+        &self.element_type
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// The number of array brackets after the parentheses, like 1 for
+    /// `int value()[]`. These add to the dimensions of the element type.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// The default value like `1` in `int value() default 1;`.
+    pub fn default(&self) -> Option<&ElementValue> {
+        self.default.as_ref()
+    }
+
+    /// The span of the Javadoc comment directly preceding this declaration.
+    pub fn doc_comment(&self) -> Option<Span> {
+        self.doc_comment
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct FieldDeclaration {
     visibility: Visibility,
     modifiers: FieldModifiers,
+    field_type: Type,
+    variables: Vec<FieldDeclarationPart>,
+    doc_comment: Option<Span>,
+}
+
+impl FieldDeclaration {
+    pub(in crate::parser) fn new(
+        visibility: Visibility,
+        modifiers: FieldModifiers,
+        field_type: Type,
+    ) -> Self {
+        Self {
+            visibility,
+            modifiers,
+            field_type,
+            variables: vec![],
+            doc_comment: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_variable(&mut self, variable: FieldDeclarationPart) {
+        self.variables.push(variable);
+    }
+
+    pub(in crate::parser) fn set_doc_comment(&mut self, doc_comment: Span) {
+        self.doc_comment = Some(doc_comment);
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn modifiers(&self) -> &FieldModifiers {
+        &self.modifiers
+    }
+
+    /// The type shared by all variables of this declaration. Array brackets
+    /// after a variable name, like in `int a[];`, are not part of it.
+    pub fn field_type(&self) -> &Type {
+        &self.field_type
+    }
+
+    /// The declared fields, like `a` and `b` in `int a, b = 1;`.
+    pub fn variables(&self) -> &[FieldDeclarationPart] {
+        &self.variables
+    }
+
+    /// The span of the Javadoc comment directly preceding this declaration.
+    pub fn doc_comment(&self) -> Option<Span> {
+        self.doc_comment
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FieldDeclarationPart {
     name: Identifier,
-    field_type: QualifiedName,
+    dimensions: usize,
     initializer: Option<Expression>,
 }
 
+impl FieldDeclarationPart {
+    pub(in crate::parser) fn new(name: Identifier) -> Self {
+        Self {
+            name,
+            dimensions: 0,
+            initializer: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_dimension(&mut self) {
+        self.dimensions += 1;
+    }
+
+    pub(in crate::parser) fn set_initializer(&mut self, initializer: Expression) {
+        self.initializer = Some(initializer);
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// The number of array brackets after the name, like 1 for `a[]`. These
+    /// add to the dimensions of the declared field type.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn initializer(&self) -> Option<&Expression> {
+        self.initializer.as_ref()
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MethodDeclaration {
     visibility: Visibility,
     modifiers: MethodModifiers,
-    return_type: Option<QualifiedName>,
+    type_parameters: Vec<TypeParameter>,
+    return_type: Option<Type>,
+    name: Identifier,
+    receiver: Option<ReceiverParameter>,
     parameters: Vec<Parameter>,
-    throws: Vec<QualifiedName>,
+    throws: Vec<Type>,
     block: Option<Block>,
+    doc_comment: Option<Span>,
+}
+
+impl MethodDeclaration {
+    pub(in crate::parser) fn new(
+        visibility: Visibility,
+        modifiers: MethodModifiers,
+        return_type: Option<Type>,
+        name: Identifier,
+    ) -> Self {
+        Self {
+            visibility,
+            modifiers,
+            type_parameters: vec![],
+            return_type,
+            name,
+            receiver: None,
+            parameters: vec![],
+            throws: vec![],
+            block: None,
+            doc_comment: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_type_parameter(&mut self, type_parameter: TypeParameter) {
+        self.type_parameters.push(type_parameter);
+    }
+
+    pub(in crate::parser) fn set_receiver(&mut self, receiver: ReceiverParameter) {
+        self.receiver = Some(receiver);
+    }
+
+    pub(in crate::parser) fn add_parameter(&mut self, parameter: Parameter) {
+        self.parameters.push(parameter);
+    }
+
+    pub(in crate::parser) fn add_throws(&mut self, exception: Type) {
+        self.throws.push(exception);
+    }
+
+    pub(in crate::parser) fn set_block(&mut self, block: Block) {
+        self.block = Some(block);
+    }
+
+    pub(in crate::parser) fn set_doc_comment(&mut self, doc_comment: Span) {
+        self.doc_comment = Some(doc_comment);
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn modifiers(&self) -> &MethodModifiers {
+        &self.modifiers
+    }
+
+    /// The type parameters declared before the return type, like `T` in
+    /// `<T> T first(List<T> list)`.
+    pub fn type_parameters(&self) -> &[TypeParameter] {
+        &self.type_parameters
+    }
+
+    /// The return type of the method, or `None` if the method returns `void`.
+    /// `void` isn't represented as a [`PrimitiveKind`](crate::PrimitiveKind), since it can only be
+    /// used in place of a return type.
+    pub fn return_type(&self) -> Option<&Type> {
+        self.return_type.as_ref()
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// The explicit receiver parameter, like `Outer.Inner this` in
+    /// `void f(Outer.Inner this)`. The receiver is not one of the [`parameters`].
+    ///
+    /// [`parameters`]: MethodDeclaration::parameters
+    pub fn receiver(&self) -> Option<&ReceiverParameter> {
+        self.receiver.as_ref()
+    }
+
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    pub fn throws(&self) -> &[Type] {
+        &self.throws
+    }
+
+    /// The body of the method, or `None` if the method has no body, like
+    /// abstract or native methods.
+    pub fn block(&self) -> Option<&Block> {
+        self.block.as_ref()
+    }
+
+    /// The span of the Javadoc comment directly preceding this declaration.
+    pub fn doc_comment(&self) -> Option<Span> {
+        self.doc_comment
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Parameter {
+    annotations: Vec<Annotation>,
     modifiers: ParameterModifiers,
     name: Identifier,
-    parameter_type: QualifiedName,
+    dimensions: usize,
+    parameter_type: Type,
+}
+
+impl Parameter {
+    pub(in crate::parser) fn new(
+        modifiers: ParameterModifiers,
+        parameter_type: Type,
+        name: Identifier,
+    ) -> Self {
+        Self {
+            annotations: vec![],
+            modifiers,
+            name,
+            dimensions: 0,
+            parameter_type,
+        }
+    }
+
+    pub(in crate::parser) fn add_dimension(&mut self) {
+        self.dimensions += 1;
+    }
+
+    pub(in crate::parser) fn add_annotation(&mut self, annotation: Annotation) {
+        self.annotations.push(annotation);
+    }
+
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    pub fn modifiers(&self) -> &ParameterModifiers {
+        &self.modifiers
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// The type of the parameter. Array brackets after the name, like in
+    /// `String args[]`, are not part of it.
+    pub fn parameter_type(&self) -> &Type {
+        &self.parameter_type
+    }
+
+    /// The number of array brackets after the name, like 1 for `args[]`.
+    /// These add to the dimensions of the parameter type.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+}
+
+/// An explicit receiver parameter, like `Outer.Inner this` in a method of
+/// `Inner`, or `Outer Outer.this` in a constructor of the inner class `Inner`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReceiverParameter {
+    annotations: Vec<Annotation>,
+    ty: Type,
+    qualifier: Option<Identifier>,
+}
+
+impl ReceiverParameter {
+    pub(in crate::parser) fn new(ty: Type, qualifier: Option<Identifier>) -> Self {
+        Self {
+            annotations: vec![],
+            ty,
+            qualifier,
+        }
+    }
+
+    pub(in crate::parser) fn add_annotation(&mut self, annotation: Annotation) {
+        self.annotations.push(annotation);
+    }
+
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// The name before `.this`, like the second `Outer` in `Outer Outer.this`.
+    /// Only the receivers of constructors have one.
+    pub fn qualifier(&self) -> Option<&Identifier> {
+        self.qualifier.as_ref()
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ConstructorDeclaration {
     visibility: Visibility,
     modifiers: MethodModifiers,
+    type_parameters: Vec<TypeParameter>,
+    name: Identifier,
+    receiver: Option<ReceiverParameter>,
     parameters: Vec<Parameter>,
-    throws: Vec<QualifiedName>,
+    throws: Vec<Type>,
     block: Block,
+    doc_comment: Option<Span>,
+}
+
+impl ConstructorDeclaration {
+    pub(in crate::parser) fn new(
+        visibility: Visibility,
+        modifiers: MethodModifiers,
+        name: Identifier,
+        block: Block,
+    ) -> Self {
+        Self {
+            visibility,
+            modifiers,
+            type_parameters: vec![],
+            name,
+            receiver: None,
+            parameters: vec![],
+            throws: vec![],
+            block,
+            doc_comment: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_type_parameter(&mut self, type_parameter: TypeParameter) {
+        self.type_parameters.push(type_parameter);
+    }
+
+    pub(in crate::parser) fn set_receiver(&mut self, receiver: ReceiverParameter) {
+        self.receiver = Some(receiver);
+    }
+
+    pub(in crate::parser) fn add_parameter(&mut self, parameter: Parameter) {
+        self.parameters.push(parameter);
+    }
+
+    pub(in crate::parser) fn add_throws(&mut self, exception: Type) {
+        self.throws.push(exception);
+    }
+
+    pub(in crate::parser) fn set_doc_comment(&mut self, doc_comment: Span) {
+        self.doc_comment = Some(doc_comment);
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn modifiers(&self) -> &MethodModifiers {
+        &self.modifiers
+    }
+
+    /// The type parameters declared before the name, like `T` in `<T> A(T t)`.
+    pub fn type_parameters(&self) -> &[TypeParameter] {
+        &self.type_parameters
+    }
+
+    /// The name of the constructor, which is the name of the declaring class.
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// The explicit receiver parameter, like `Outer Outer.this` in the
+    /// constructor `Inner(Outer Outer.this)` of the inner class `Inner`.
+    pub fn receiver(&self) -> Option<&ReceiverParameter> {
+        self.receiver.as_ref()
+    }
+
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    pub fn throws(&self) -> &[Type] {
+        &self.throws
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+
+    /// The span of the Javadoc comment directly preceding this declaration.
+    pub fn doc_comment(&self) -> Option<Span> {
+        self.doc_comment
+    }
 }
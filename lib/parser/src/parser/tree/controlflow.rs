@@ -5,12 +5,41 @@ pub struct BreakStatement {
     label: Option<Identifier>,
 }
 
+impl BreakStatement {
+    pub(in crate::parser) fn new(label: Option<Identifier>) -> Self {
+        Self { label }
+    }
+
+    /// The label of the statement to break out of, like `outer` in
+    /// `break outer;`.
+    pub fn label(&self) -> Option<&Identifier> {
+        self.label.as_ref()
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ContinueStatement {
     label: Option<Identifier>,
 }
 
+impl ContinueStatement {
+    pub(in crate::parser) fn new(label: Option<Identifier>) -> Self {
+        Self { label }
+    }
+
+    /// The label of the loop to continue, like `outer` in `continue outer;`.
+    pub fn label(&self) -> Option<&Identifier> {
+        self.label.as_ref()
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ReturnStatement {
     expression: Option<Expression>,
 }
+
+impl ReturnStatement {
+    pub fn expression(&self) -> Option<&Expression> {
+        self.expression.as_ref()
+    }
+}
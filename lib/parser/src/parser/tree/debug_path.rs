@@ -0,0 +1,180 @@
+/// Tracks the path to the current line of a pretty-printed `{:#?}` output,
+/// like `types[0].Class.members[1].Method.name`, while its lines are fed
+/// one by one.
+///
+/// Fields contribute their name, elements of lists their index, and tuple
+/// variants like `Method(` their variant name. Struct names and `Some` are
+/// left out, since the field or variant that holds them already names them.
+#[derive(Default)]
+pub(super) struct DebugPath {
+    frames: Vec<Frame>,
+}
+
+struct Frame {
+    depth: usize,
+    segments: Vec<String>,
+    is_list: bool,
+    children: usize,
+}
+
+impl DebugPath {
+    /// Advances the path past the given line.
+    pub(super) fn push_line(&mut self, line: &str) {
+        let (depth, text) = split_indent(line);
+        while self.frames.last().is_some_and(|frame| frame.depth >= depth) {
+            self.frames.pop();
+        }
+        if is_closing(text) {
+            return;
+        }
+
+        let segments = self.segments(text);
+        if let Some(parent) = self.frames.last_mut().filter(|frame| frame.is_list) {
+            parent.children += 1;
+        }
+        if text.ends_with(['{', '(', '[']) {
+            self.frames.push(Frame {
+                depth,
+                segments,
+                is_list: text.ends_with('['),
+                children: 0,
+            });
+        }
+    }
+
+    /// Describes the path to the given line, which is the line following
+    /// all lines pushed so far.
+    pub(super) fn describe(&self, line: &str) -> String {
+        let (depth, text) = split_indent(line);
+        let frames = self.frames.iter().filter(|frame| frame.depth < depth);
+        let own = if is_closing(text) {
+            vec![]
+        } else {
+            self.segments(text)
+        };
+
+        let mut path = String::new();
+        for segment in frames.flat_map(|frame| &frame.segments).chain(&own) {
+            if !path.is_empty() && !segment.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(segment);
+        }
+        path
+    }
+
+    fn segments(&self, text: &str) -> Vec<String> {
+        let mut segments = vec![];
+        let value = match text.split_once(": ") {
+            Some((field, value)) if is_field_name(field) => {
+                segments.push(field.to_string());
+                value
+            }
+            _ => {
+                if let Some(parent) = self.frames.last().filter(|frame| frame.is_list) {
+                    segments.push(format!("[{}]", parent.children));
+                }
+                text
+            }
+        };
+        if let Some(variant) = value.strip_suffix('(') {
+            if variant != "Some" {
+                segments.push(variant.to_string());
+            }
+        }
+        segments
+    }
+}
+
+/// Returns the value on the given line without its field name and trailing
+/// comma, like `GraphemeIndex(9)` for `end: GraphemeIndex(9),`.
+pub(super) fn value(line: &str) -> &str {
+    let (_, text) = split_indent(line);
+    let value = match text.split_once(": ") {
+        Some((field, value)) if is_field_name(field) => value,
+        _ => text,
+    };
+    value.strip_suffix(',').unwrap_or(value)
+}
+
+/// Splits a line into its depth, which `{:#?}` indents by four spaces per
+/// level, and its trimmed text.
+fn split_indent(line: &str) -> (usize, &str) {
+    let text = line.trim_start();
+    ((line.len() - text.len()) / 4, text.trim_end())
+}
+
+fn is_closing(text: &str) -> bool {
+    text.starts_with(['}', ')', ']'])
+}
+
+fn is_field_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '#')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct Outer {
+        items: Vec<Vec<u8>>,
+        kinds: Vec<Kind>,
+        maybe: Option<Inner>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    enum Kind {
+        Unit,
+        Leaf(Inner),
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct Inner {
+        value: u8,
+    }
+
+    /// Returns the path to the first line of the `{:#?}` output that
+    /// contains the needle.
+    fn path_to(value: &impl std::fmt::Debug, needle: &str) -> String {
+        let debug = format!("{:#?}", value);
+        let mut path = DebugPath::default();
+        for line in debug.lines() {
+            if line.contains(needle) {
+                return path.describe(line);
+            }
+            path.push_line(line);
+        }
+        panic!("{:?} not found in {}", needle, debug);
+    }
+
+    #[test]
+    fn test_paths() {
+        let outer = Outer {
+            items: vec![vec![1], vec![2, 3]],
+            kinds: vec![Kind::Unit, Kind::Leaf(Inner { value: 4 })],
+            maybe: Some(Inner { value: 5 }),
+        };
+        assert_eq!(path_to(&outer, "items"), "items");
+        assert_eq!(path_to(&outer, "1,"), "items[0][0]");
+        assert_eq!(path_to(&outer, "3,"), "items[1][1]");
+        assert_eq!(path_to(&outer, "Unit"), "kinds[0]");
+        assert_eq!(path_to(&outer, "Leaf"), "kinds[1].Leaf");
+        assert_eq!(path_to(&outer, "value: 4"), "kinds[1].Leaf.value");
+        assert_eq!(path_to(&outer, "value: 5"), "maybe.value");
+        // a closing line belongs to the value that it closes
+        assert_eq!(path_to(&outer, "}"), "kinds[1].Leaf");
+    }
+
+    #[test]
+    fn test_value() {
+        assert_eq!(value("        end: GraphemeIndex(9),"), "GraphemeIndex(9)");
+        assert_eq!(value("    Leaf("), "Leaf(");
+        assert_eq!(value("    3,"), "3");
+    }
+}
@@ -5,3 +5,18 @@ pub struct DoWhileStatement {
     condition: Expression,
     block: Block,
 }
+
+impl DoWhileStatement {
+    pub(in crate::parser) fn new(block: Block, condition: Expression) -> Self {
+        Self { condition, block }
+    }
+
+    /// The condition after `while`, which is evaluated after each iteration.
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+}
@@ -1,11 +1,18 @@
+use crate::lexer::source::Source;
 use crate::parser::tree::local_var_decl::LocalVariableDeclaration;
-use crate::{Block, Expression, Identifier, ParameterModifiers, QualifiedName};
+use crate::{Block, Expression, Identifier, ParameterModifiers, Type};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ThrowStatement {
     expression: Expression,
 }
 
+impl ThrowStatement {
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct TryStatement {
     block: Block,
@@ -14,20 +21,115 @@ pub struct TryStatement {
     finally: Option<Block>,
 }
 
+impl TryStatement {
+    pub(in crate::parser) fn new(block: Block) -> Self {
+        Self {
+            block,
+            resources: vec![],
+            catches: vec![],
+            finally: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_catch(&mut self, catch: CatchClause) {
+        self.catches.push(catch);
+    }
+
+    pub(in crate::parser) fn set_finally(&mut self, finally: Block) {
+        self.finally = Some(finally);
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+
+    pub fn resources(&self) -> &[TryResource] {
+        &self.resources
+    }
+
+    pub fn catches(&self) -> &[CatchClause] {
+        &self.catches
+    }
+
+    pub fn finally(&self) -> Option<&Block> {
+        self.finally.as_ref()
+    }
+
+    /// Returns the names of all exception types caught by the catch clauses
+    /// of this statement, including every alternative of a multi-catch, in the
+    /// order in which they are declared. The names are returned as they are
+    /// written.
+    pub fn caught_types(&self, source: &Source) -> Vec<String> {
+        self.catches
+            .iter()
+            .flat_map(|catch| catch.parameter().ty())
+            .filter_map(|ty| ty.name()?.resolve(source))
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct TryResource {
     variable: LocalVariableDeclaration,
 }
 
+impl TryResource {
+    pub fn variable(&self) -> &LocalVariableDeclaration {
+        &self.variable
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CatchClause {
     parameter: CatchParameter,
     block: Block,
 }
 
+impl CatchClause {
+    pub(in crate::parser) fn new(parameter: CatchParameter, block: Block) -> Self {
+        Self { parameter, block }
+    }
+
+    pub fn parameter(&self) -> &CatchParameter {
+        &self.parameter
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CatchParameter {
     modifiers: ParameterModifiers,
     name: Identifier,
-    ty: Vec<QualifiedName>,
+    ty: Vec<Type>,
+}
+
+impl CatchParameter {
+    pub(in crate::parser) fn new(
+        modifiers: ParameterModifiers,
+        ty: Vec<Type>,
+        name: Identifier,
+    ) -> Self {
+        Self {
+            modifiers,
+            name,
+            ty,
+        }
+    }
+
+    pub fn modifiers(&self) -> &ParameterModifiers {
+        &self.modifiers
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// The caught exception types, which are more than one in a multi-catch
+    /// like `catch (A | B e)`.
+    pub fn ty(&self) -> &[Type] {
+        &self.ty
+    }
 }
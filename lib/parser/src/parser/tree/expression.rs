@@ -1,10 +1,28 @@
-use crate::lexer::span::Span;
+use crate::lexer::span::{Span, Spanned};
+use crate::lexer::token::Radix;
 use crate::parser::tree::qualified_name::QualifiedName;
+use crate::{ClassMember, Identifier, Type};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Expression {
     StringLiteral(StringLiteral),
+    BooleanLiteral(BooleanLiteral),
+    IntegerLiteral(IntegerLiteral),
+    NullLiteral(NullLiteral),
+    Name(QualifiedName),
+    This(This),
+    Parenthesized(Box<Expression>),
+    ObjectCreation(ObjectCreation),
+    ArrayCreation(ArrayCreation),
+    FieldAccess(FieldAccess),
     MethodCall(MethodCall),
+    Cast(Cast),
+    ArrayAccess(ArrayAccess),
+    Conditional(Conditional),
+    Unary(Unary),
+    Binary(Binary),
+    InstanceOf(InstanceOf),
+    Assignment(Assignment),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -12,8 +30,507 @@ pub struct StringLiteral {
     span: Span,
 }
 
+impl StringLiteral {
+    pub(in crate::parser) fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+impl Spanned for StringLiteral {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BooleanLiteral {
+    span: Span,
+}
+
+impl BooleanLiteral {
+    pub(in crate::parser) fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+impl Spanned for BooleanLiteral {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IntegerLiteral {
+    span: Span,
+    radix: Radix,
+    long: bool,
+}
+
+impl IntegerLiteral {
+    pub(in crate::parser) fn new(span: Span, radix: Radix, long: bool) -> Self {
+        Self { span, radix, long }
+    }
+
+    /// The radix in which the literal was written in the source.
+    pub fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    /// Whether the literal carries an `L` or `l` suffix and thus is of type `long`.
+    pub fn is_long(&self) -> bool {
+        self.long
+    }
+}
+
+impl Spanned for IntegerLiteral {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NullLiteral {
+    span: Span,
+}
+
+impl NullLiteral {
+    pub(in crate::parser) fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+impl Spanned for NullLiteral {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+/// A `this` expression, which may be qualified with the name of an
+/// enclosing class, like `Outer.this`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct This {
+    qualifier: Option<QualifiedName>,
+    span: Span,
+}
+
+impl This {
+    pub(in crate::parser) fn new(qualifier: Option<QualifiedName>, span: Span) -> Self {
+        Self { qualifier, span }
+    }
+
+    /// The name of the enclosing class, like `Outer` in `Outer.this`.
+    pub fn qualifier(&self) -> Option<&QualifiedName> {
+        self.qualifier.as_ref()
+    }
+}
+
+impl Spanned for This {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+/// An instance creation like `new Foo(a, b)`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ObjectCreation {
+    ty: Type,
+    arguments: Vec<Expression>,
+    body: Option<AnonymousClassBody>,
+}
+
+impl ObjectCreation {
+    pub(in crate::parser) fn new(ty: Type, arguments: Vec<Expression>) -> Self {
+        Self {
+            ty,
+            arguments,
+            body: None,
+        }
+    }
+
+    pub(in crate::parser) fn set_body(&mut self, body: AnonymousClassBody) {
+        self.body = Some(body);
+    }
+
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    pub fn arguments(&self) -> &[Expression] {
+        &self.arguments
+    }
+
+    /// The body of the anonymous class, like `{ ... }` in `new Runnable() { ... }`.
+    pub fn body(&self) -> Option<&AnonymousClassBody> {
+        self.body.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AnonymousClassBody {
+    members: Vec<ClassMember>,
+}
+
+impl AnonymousClassBody {
+    pub(in crate::parser) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(in crate::parser) fn add_member(&mut self, member: ClassMember) {
+        self.members.push(member);
+    }
+
+    pub fn members(&self) -> &[ClassMember] {
+        &self.members
+    }
+}
+
+/// An array creation like `new int[3][]` or `new int[] { 1, 2 }`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ArrayCreation {
+    ty: Type,
+    dimensions: Vec<Expression>,
+    extra_dimensions: usize,
+    initializer: Option<ArrayInitializer>,
+}
+
+impl ArrayCreation {
+    pub(in crate::parser) fn new(ty: Type) -> Self {
+        Self {
+            ty,
+            dimensions: vec![],
+            extra_dimensions: 0,
+            initializer: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_dimension(&mut self, dimension: Expression) {
+        self.dimensions.push(dimension);
+    }
+
+    pub(in crate::parser) fn add_extra_dimension(&mut self) {
+        self.extra_dimensions += 1;
+    }
+
+    pub(in crate::parser) fn set_initializer(&mut self, initializer: ArrayInitializer) {
+        self.initializer = Some(initializer);
+    }
+
+    /// The type as written before any dimension expression, like `int` in
+    /// `new int[3][]` or `int[]` in `new int[] { 1, 2 }`.
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// The dimension expressions, like `3` in `new int[3][]`.
+    pub fn dimensions(&self) -> &[Expression] {
+        &self.dimensions
+    }
+
+    /// The number of empty brackets after the dimension expressions, like 1
+    /// for `new int[3][]`.
+    pub fn extra_dimensions(&self) -> usize {
+        self.extra_dimensions
+    }
+
+    pub fn initializer(&self) -> Option<&ArrayInitializer> {
+        self.initializer.as_ref()
+    }
+}
+
+/// An array initializer like `{ 1, 2, 3 }` or `{ { 1 }, { 2 } }`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ArrayInitializer {
+    elements: Vec<ArrayElement>,
+    span: Span,
+}
+
+impl ArrayInitializer {
+    pub(in crate::parser) fn new(elements: Vec<ArrayElement>, span: Span) -> Self {
+        Self { elements, span }
+    }
+
+    pub fn elements(&self) -> &[ArrayElement] {
+        &self.elements
+    }
+}
+
+impl Spanned for ArrayInitializer {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ArrayElement {
+    Expression(Expression),
+    /// A nested initializer of a multi-dimensional array.
+    Initializer(ArrayInitializer),
+}
+
+/// An access of a field on an expression that is not a plain name, like
+/// `foo().bar`. Accesses on names, like `foo.bar`, are represented as
+/// [`Expression::Name`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FieldAccess {
+    receiver: Box<Expression>,
+    name: Identifier,
+}
+
+impl FieldAccess {
+    pub(in crate::parser) fn new(receiver: Expression, name: Identifier) -> Self {
+        Self {
+            receiver: Box::new(receiver),
+            name,
+        }
+    }
+
+    pub fn receiver(&self) -> &Expression {
+        &self.receiver
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+}
+
+/// A method call like `System.out.println(x)` or `"hi".length()`.
+///
+/// If the method is called on a plain name, the whole name is stored in
+/// `name` and there is no receiver. If it is called on any other expression,
+/// like a literal or the result of another call, that expression is the
+/// receiver and `name` only holds the method name.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MethodCall {
+    receiver: Option<Box<Expression>>,
     name: QualifiedName,
     arguments: Vec<Expression>,
 }
+
+impl MethodCall {
+    pub(in crate::parser) fn new(
+        receiver: Option<Expression>,
+        name: QualifiedName,
+        arguments: Vec<Expression>,
+    ) -> Self {
+        Self {
+            receiver: receiver.map(Box::new),
+            name,
+            arguments,
+        }
+    }
+
+    pub fn receiver(&self) -> Option<&Expression> {
+        self.receiver.as_deref()
+    }
+
+    pub fn name(&self) -> &QualifiedName {
+        &self.name
+    }
+
+    pub fn arguments(&self) -> &[Expression] {
+        &self.arguments
+    }
+}
+
+/// A cast like `(String) x`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cast {
+    ty: Type,
+    expression: Box<Expression>,
+}
+
+impl Cast {
+    pub(in crate::parser) fn new(ty: Type, expression: Expression) -> Self {
+        Self {
+            ty,
+            expression: Box::new(expression),
+        }
+    }
+
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
+
+/// An access of an array element like `a[i]`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ArrayAccess {
+    array: Box<Expression>,
+    index: Box<Expression>,
+}
+
+impl ArrayAccess {
+    pub(in crate::parser) fn new(array: Expression, index: Expression) -> Self {
+        Self {
+            array: Box::new(array),
+            index: Box::new(index),
+        }
+    }
+
+    pub fn array(&self) -> &Expression {
+        &self.array
+    }
+
+    pub fn index(&self) -> &Expression {
+        &self.index
+    }
+}
+
+/// A conditional expression like `a ? b : c`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Conditional {
+    condition: Box<Expression>,
+    then: Box<Expression>,
+    otherwise: Box<Expression>,
+}
+
+impl Conditional {
+    pub(in crate::parser) fn new(
+        condition: Expression,
+        then: Expression,
+        otherwise: Expression,
+    ) -> Self {
+        Self {
+            condition: Box::new(condition),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        }
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    /// The expression that is evaluated if the condition is true.
+    pub fn then(&self) -> &Expression {
+        &self.then
+    }
+
+    /// The expression that is evaluated if the condition is false.
+    pub fn otherwise(&self) -> &Expression {
+        &self.otherwise
+    }
+}
+
+/// A unary operation like `-a`, `!a` or `++a`, or a postfix increment or
+/// decrement like `a++`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Unary {
+    operator: Span,
+    operand: Box<Expression>,
+    postfix: bool,
+}
+
+impl Unary {
+    pub(in crate::parser) fn new(operator: Span, operand: Expression, postfix: bool) -> Self {
+        Self {
+            operator,
+            operand: Box::new(operand),
+            postfix,
+        }
+    }
+
+    /// The span of the operator, which resolves to its text like `-` or `++`.
+    pub fn operator(&self) -> Span {
+        self.operator
+    }
+
+    pub fn operand(&self) -> &Expression {
+        &self.operand
+    }
+
+    /// Whether the operator is written after the operand, like in `a++`.
+    pub fn is_postfix(&self) -> bool {
+        self.postfix
+    }
+}
+
+/// A binary operation like `a + b` or `a && b`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Binary {
+    left: Box<Expression>,
+    operator: Span,
+    right: Box<Expression>,
+}
+
+impl Binary {
+    pub(in crate::parser) fn new(left: Expression, operator: Span, right: Expression) -> Self {
+        Self {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        }
+    }
+
+    pub fn left(&self) -> &Expression {
+        &self.left
+    }
+
+    /// The span of the operator, which resolves to its text like `+`.
+    pub fn operator(&self) -> Span {
+        self.operator
+    }
+
+    pub fn right(&self) -> &Expression {
+        &self.right
+    }
+}
+
+/// A type comparison like `a instanceof String`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InstanceOf {
+    expression: Box<Expression>,
+    ty: Type,
+}
+
+impl InstanceOf {
+    pub(in crate::parser) fn new(expression: Expression, ty: Type) -> Self {
+        Self {
+            expression: Box::new(expression),
+            ty,
+        }
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+}
+
+/// An assignment like `a = b` or a compound assignment like `a += b`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Assignment {
+    target: Box<Expression>,
+    operator: Span,
+    value: Box<Expression>,
+}
+
+impl Assignment {
+    pub(in crate::parser) fn new(target: Expression, operator: Span, value: Expression) -> Self {
+        Self {
+            target: Box::new(target),
+            operator,
+            value: Box::new(value),
+        }
+    }
+
+    pub fn target(&self) -> &Expression {
+        &self.target
+    }
+
+    /// The span of the operator, which resolves to its text like `=` or `+=`.
+    pub fn operator(&self) -> Span {
+        self.operator
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+}
@@ -1,5 +1,5 @@
 use crate::parser::tree::local_var_decl::LocalVariableDeclaration;
-use crate::{Block, Expression, Identifier, ParameterModifiers, QualifiedName};
+use crate::{Block, Expression, Identifier, ParameterModifiers, Type};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ForStatement {
@@ -9,6 +9,38 @@ pub struct ForStatement {
     block: Block,
 }
 
+impl ForStatement {
+    pub(in crate::parser) fn new(
+        initializers: Vec<ForInitializer>,
+        condition: Option<Expression>,
+        updaters: Vec<Expression>,
+        block: Block,
+    ) -> Self {
+        Self {
+            initializers,
+            condition,
+            updaters,
+            block,
+        }
+    }
+
+    pub fn initializers(&self) -> &[ForInitializer] {
+        &self.initializers
+    }
+
+    pub fn condition(&self) -> Option<&Expression> {
+        self.condition.as_ref()
+    }
+
+    pub fn updaters(&self) -> &[Expression] {
+        &self.updaters
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ForInitializer {
     LocalVariableDeclaration(LocalVariableDeclaration),
@@ -22,9 +54,62 @@ pub struct ForEachStatement {
     block: Block,
 }
 
+impl ForEachStatement {
+    pub(in crate::parser) fn new(
+        variable: ForEachVariableDeclaration,
+        expression: Expression,
+        block: Block,
+    ) -> Self {
+        Self {
+            variable,
+            expression,
+            block,
+        }
+    }
+
+    pub fn variable(&self) -> &ForEachVariableDeclaration {
+        &self.variable
+    }
+
+    /// The expression that is iterated over, like `list` in `for (String s : list)`.
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ForEachVariableDeclaration {
     modifiers: ParameterModifiers,
-    ty: QualifiedName,
+    ty: Type,
     name: Identifier,
 }
+
+impl ForEachVariableDeclaration {
+    pub(in crate::parser) fn new(
+        modifiers: ParameterModifiers,
+        ty: Type,
+        name: Identifier,
+    ) -> Self {
+        Self {
+            modifiers,
+            ty,
+            name,
+        }
+    }
+
+    pub fn modifiers(&self) -> &ParameterModifiers {
+        &self.modifiers
+    }
+
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+}
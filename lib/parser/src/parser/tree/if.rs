@@ -8,8 +8,62 @@ pub struct IfStatement {
     else_statement: Option<Block>,
 }
 
+impl IfStatement {
+    pub(in crate::parser) fn new(condition: Expression, then_statement: Block) -> Self {
+        Self {
+            condition,
+            then_statement,
+            else_ifs: vec![],
+            else_statement: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_else_if(&mut self, else_if: ElseIfStatement) {
+        self.else_ifs.push(else_if);
+    }
+
+    pub(in crate::parser) fn set_else_statement(&mut self, else_statement: Block) {
+        self.else_statement = Some(else_statement);
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn then_statement(&self) -> &Block {
+        &self.then_statement
+    }
+
+    /// The `else if` branches, in source order.
+    pub fn else_ifs(&self) -> &[ElseIfStatement] {
+        &self.else_ifs
+    }
+
+    /// The final `else` branch, if there is one.
+    pub fn else_statement(&self) -> Option<&Block> {
+        self.else_statement.as_ref()
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ElseIfStatement {
     condition: Expression,
     statement: Block,
 }
+
+impl ElseIfStatement {
+    pub(in crate::parser) fn new(condition: Expression, statement: Block) -> Self {
+        Self {
+            condition,
+            statement,
+        }
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn statement(&self) -> &Block {
+        &self.statement
+    }
+}
@@ -1,14 +1,74 @@
-use crate::{Expression, Identifier, ParameterModifiers, QualifiedName};
+use crate::{Expression, Identifier, ParameterModifiers, Type};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct LocalVariableDeclaration {
     modifiers: ParameterModifiers,
-    ty: QualifiedName,
+    ty: Type,
     variables: Vec<LocalVariableDeclarationPart>,
 }
 
+impl LocalVariableDeclaration {
+    pub(in crate::parser) fn new(modifiers: ParameterModifiers, ty: Type) -> Self {
+        Self {
+            modifiers,
+            ty,
+            variables: vec![],
+        }
+    }
+
+    pub(in crate::parser) fn add_variable(&mut self, variable: LocalVariableDeclarationPart) {
+        self.variables.push(variable);
+    }
+
+    pub fn modifiers(&self) -> &ParameterModifiers {
+        &self.modifiers
+    }
+
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// The declared variables, like `a` and `b` in `int a, b = 1;`.
+    pub fn variables(&self) -> &[LocalVariableDeclarationPart] {
+        &self.variables
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct LocalVariableDeclarationPart {
     name: Identifier,
+    dimensions: usize,
     value: Option<Expression>,
 }
+
+impl LocalVariableDeclarationPart {
+    pub(in crate::parser) fn new(name: Identifier) -> Self {
+        Self {
+            name,
+            dimensions: 0,
+            value: None,
+        }
+    }
+
+    pub(in crate::parser) fn add_dimension(&mut self) {
+        self.dimensions += 1;
+    }
+
+    pub(in crate::parser) fn set_value(&mut self, value: Expression) {
+        self.value = Some(value);
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// The number of array brackets after the name, like 1 for `a[]`. These
+    /// add to the dimensions of the declared type.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn value(&self) -> Option<&Expression> {
+        self.value.as_ref()
+    }
+}
@@ -1,3 +1,4 @@
+pub use annotation::*;
 pub use assert::*;
 pub use block::*;
 pub use compilation_unit::*;
@@ -8,18 +9,22 @@ pub use expression::*;
 pub use identifier::*;
 pub use local_var_decl::*;
 pub use modifiers::*;
+pub use preorder::*;
 pub use qualified_name::*;
 pub use r#for::*;
 pub use r#if::*;
+pub use r#type::*;
 pub use r#while::*;
 pub use statement::*;
 pub use switch::*;
 pub use synchronized::*;
 
+mod annotation;
 mod assert;
 mod block;
 mod compilation_unit;
 mod controlflow;
+mod debug_path;
 mod do_while;
 mod exception_handling;
 mod expression;
@@ -28,8 +33,10 @@ mod identifier;
 mod r#if;
 mod local_var_decl;
 mod modifiers;
+mod preorder;
 mod qualified_name;
 mod statement;
 mod switch;
 mod synchronized;
+mod r#type;
 mod r#while;
@@ -0,0 +1,294 @@
+use crate::{
+    AnnotationElement, AnnotationMember, ArrayElement, ArrayInitializer, Block, CaseLabel,
+    ClassMember, ConstructorDeclaration, ElementValue, EnumMember, Expression, FieldDeclaration,
+    ForInitializer, Identifier, InterfaceMember, LocalVariableDeclaration, MethodDeclaration,
+    Statement, StatementKind, TypeDeclaration,
+};
+
+/// A node of the tree, as yielded by [`CompilationUnit::preorder`].
+///
+/// Blocks are not nodes of their own, their statements are the children of
+/// the enclosing node.
+///
+/// [`CompilationUnit::preorder`]: crate::CompilationUnit::preorder
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Node<'a> {
+    Type(&'a TypeDeclaration),
+    Field(&'a FieldDeclaration),
+    Method(&'a MethodDeclaration),
+    Constructor(&'a ConstructorDeclaration),
+    AnnotationElement(&'a AnnotationElement),
+    EnumConstant(&'a Identifier),
+    Statement(&'a Statement),
+    Expression(&'a Expression),
+}
+
+/// An iterator over nodes in pre-order, where each node is followed by its
+/// children in source order.
+pub struct Preorder<'a> {
+    /// The nodes yet to visit, where the next node is at the end.
+    stack: Vec<Node<'a>>,
+}
+
+impl<'a> Preorder<'a> {
+    pub(in crate::parser) fn new(types: &'a [TypeDeclaration]) -> Self {
+        Self {
+            stack: types.iter().rev().map(Node::Type).collect(),
+        }
+    }
+}
+
+impl<'a> Iterator for Preorder<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        // children are collected in source order, but the first one must be
+        // visited next, so it has to end up at the end of the stack
+        let start = self.stack.len();
+        node.children(&mut self.stack);
+        self.stack[start..].reverse();
+        Some(node)
+    }
+}
+
+impl<'a> Node<'a> {
+    /// Appends the direct children of this node to `out`, in source order.
+    fn children(self, out: &mut Vec<Node<'a>>) {
+        match self {
+            Node::Type(ty) => type_children(ty, out),
+            Node::Field(field) => out.extend(
+                field
+                    .variables()
+                    .iter()
+                    .filter_map(|variable| variable.initializer())
+                    .map(Node::Expression),
+            ),
+            Node::Method(method) => {
+                if let Some(block) = method.block() {
+                    block_children(block, out);
+                }
+            }
+            Node::Constructor(constructor) => block_children(constructor.block(), out),
+            Node::AnnotationElement(element) => {
+                if let Some(default) = element.default() {
+                    element_value_children(default, out);
+                }
+            }
+            Node::EnumConstant(_) => {}
+            Node::Statement(statement) => statement_children(statement, out),
+            Node::Expression(expression) => expression_children(expression, out),
+        }
+    }
+}
+
+fn type_children<'a>(ty: &'a TypeDeclaration, out: &mut Vec<Node<'a>>) {
+    match ty {
+        TypeDeclaration::Class(class) => {
+            out.extend(class.members().iter().map(class_member));
+        }
+        TypeDeclaration::Interface(interface) => {
+            out.extend(interface.members().iter().map(|member| match member {
+                InterfaceMember::Type(ty) => Node::Type(ty),
+                InterfaceMember::Method(method) => Node::Method(method),
+            }));
+        }
+        TypeDeclaration::Enum(enumeration) => {
+            out.extend(enumeration.members().iter().map(|member| match member {
+                EnumMember::EnumConstant(name) => Node::EnumConstant(name),
+                EnumMember::Type(ty) => Node::Type(ty),
+                EnumMember::Field(field) => Node::Field(field),
+                EnumMember::Method(method) => Node::Method(method),
+                EnumMember::Constructor(constructor) => Node::Constructor(constructor),
+            }));
+        }
+        TypeDeclaration::Annotation(annotation) => {
+            out.extend(annotation.members().iter().map(|member| match member {
+                AnnotationMember::Type(ty) => Node::Type(ty),
+                AnnotationMember::Field(field) => Node::Field(field),
+                AnnotationMember::Element(element) => Node::AnnotationElement(element),
+            }));
+        }
+    }
+}
+
+fn class_member(member: &ClassMember) -> Node<'_> {
+    match member {
+        ClassMember::Type(ty) => Node::Type(ty),
+        ClassMember::Field(field) => Node::Field(field),
+        ClassMember::Method(method) => Node::Method(method),
+        ClassMember::Constructor(constructor) => Node::Constructor(constructor),
+    }
+}
+
+fn block_children<'a>(block: &'a Block, out: &mut Vec<Node<'a>>) {
+    out.extend(block.statements().iter().map(Node::Statement));
+}
+
+fn local_variable_children<'a>(declaration: &'a LocalVariableDeclaration, out: &mut Vec<Node<'a>>) {
+    out.extend(
+        declaration
+            .variables()
+            .iter()
+            .filter_map(|variable| variable.value())
+            .map(Node::Expression),
+    );
+}
+
+fn statement_children<'a>(statement: &'a Statement, out: &mut Vec<Node<'a>>) {
+    match statement.kind() {
+        StatementKind::Empty | StatementKind::Break(_) | StatementKind::Continue(_) => {}
+        StatementKind::Block(block) => block_children(block, out),
+        StatementKind::Expression(expression) => out.push(Node::Expression(expression)),
+        StatementKind::If(statement) => {
+            out.push(Node::Expression(statement.condition()));
+            block_children(statement.then_statement(), out);
+            for else_if in statement.else_ifs() {
+                out.push(Node::Expression(else_if.condition()));
+                block_children(else_if.statement(), out);
+            }
+            if let Some(block) = statement.else_statement() {
+                block_children(block, out);
+            }
+        }
+        StatementKind::Switch(statement) => {
+            out.push(Node::Expression(statement.selector()));
+            for case in statement.cases() {
+                for label in case.labels() {
+                    if let CaseLabel::Expression(expression) = label {
+                        out.push(Node::Expression(expression));
+                    }
+                }
+                out.extend(case.statements().iter().map(Node::Statement));
+            }
+        }
+        StatementKind::While(statement) => {
+            out.push(Node::Expression(statement.condition()));
+            block_children(statement.block(), out);
+        }
+        StatementKind::DoWhile(statement) => {
+            block_children(statement.block(), out);
+            out.push(Node::Expression(statement.condition()));
+        }
+        StatementKind::For(statement) => {
+            for initializer in statement.initializers() {
+                match initializer {
+                    ForInitializer::LocalVariableDeclaration(declaration) => {
+                        local_variable_children(declaration, out)
+                    }
+                    ForInitializer::Expression(expression) => {
+                        out.push(Node::Expression(expression))
+                    }
+                }
+            }
+            out.extend(statement.condition().map(Node::Expression));
+            out.extend(statement.updaters().iter().map(Node::Expression));
+            block_children(statement.block(), out);
+        }
+        StatementKind::ForEach(statement) => {
+            out.push(Node::Expression(statement.expression()));
+            block_children(statement.block(), out);
+        }
+        StatementKind::Return(statement) => {
+            out.extend(statement.expression().map(Node::Expression));
+        }
+        StatementKind::Synchronized(statement) => {
+            out.push(Node::Expression(statement.expression()));
+            block_children(statement.block(), out);
+        }
+        StatementKind::Throw(statement) => out.push(Node::Expression(statement.expression())),
+        StatementKind::Try(statement) => {
+            for resource in statement.resources() {
+                local_variable_children(resource.variable(), out);
+            }
+            block_children(statement.block(), out);
+            for catch in statement.catches() {
+                block_children(catch.block(), out);
+            }
+            if let Some(finally) = statement.finally() {
+                block_children(finally, out);
+            }
+        }
+        StatementKind::Assert(statement) => {
+            out.push(Node::Expression(statement.condition()));
+            out.extend(statement.detail().map(Node::Expression));
+        }
+        StatementKind::LocalVariableDeclaration(declaration) => {
+            local_variable_children(declaration, out)
+        }
+    }
+}
+
+fn expression_children<'a>(expression: &'a Expression, out: &mut Vec<Node<'a>>) {
+    match expression {
+        Expression::StringLiteral(_)
+        | Expression::BooleanLiteral(_)
+        | Expression::IntegerLiteral(_)
+        | Expression::NullLiteral(_)
+        | Expression::Name(_)
+        | Expression::This(_) => {}
+        Expression::Parenthesized(expression) => out.push(Node::Expression(expression)),
+        Expression::ObjectCreation(creation) => {
+            out.extend(creation.arguments().iter().map(Node::Expression));
+            if let Some(body) = creation.body() {
+                out.extend(body.members().iter().map(class_member));
+            }
+        }
+        Expression::ArrayCreation(creation) => {
+            out.extend(creation.dimensions().iter().map(Node::Expression));
+            if let Some(initializer) = creation.initializer() {
+                array_initializer_children(initializer, out);
+            }
+        }
+        Expression::FieldAccess(access) => out.push(Node::Expression(access.receiver())),
+        Expression::MethodCall(call) => {
+            out.extend(call.receiver().map(Node::Expression));
+            out.extend(call.arguments().iter().map(Node::Expression));
+        }
+        Expression::Cast(cast) => out.push(Node::Expression(cast.expression())),
+        Expression::ArrayAccess(access) => {
+            out.push(Node::Expression(access.array()));
+            out.push(Node::Expression(access.index()));
+        }
+        Expression::Conditional(conditional) => {
+            out.push(Node::Expression(conditional.condition()));
+            out.push(Node::Expression(conditional.then()));
+            out.push(Node::Expression(conditional.otherwise()));
+        }
+        Expression::Unary(unary) => out.push(Node::Expression(unary.operand())),
+        Expression::InstanceOf(instance_of) => out.push(Node::Expression(instance_of.expression())),
+        Expression::Binary(binary) => {
+            out.push(Node::Expression(binary.left()));
+            out.push(Node::Expression(binary.right()));
+        }
+        Expression::Assignment(assignment) => {
+            out.push(Node::Expression(assignment.target()));
+            out.push(Node::Expression(assignment.value()));
+        }
+    }
+}
+
+/// The expressions of an element value are flattened like the elements of
+/// array initializers, and nested annotations are not nodes.
+fn element_value_children<'a>(value: &'a ElementValue, out: &mut Vec<Node<'a>>) {
+    match value {
+        ElementValue::Expression(expression) => out.push(Node::Expression(expression)),
+        ElementValue::Array(array) => {
+            for value in array.values() {
+                element_value_children(value, out);
+            }
+        }
+        ElementValue::Annotation(_) => {}
+    }
+}
+
+/// Nested initializers like the inner ones in `{{1}, {2}}` are not nodes, so
+/// their elements are flattened into the children of the array creation.
+fn array_initializer_children<'a>(initializer: &'a ArrayInitializer, out: &mut Vec<Node<'a>>) {
+    for element in initializer.elements() {
+        match element {
+            ArrayElement::Expression(expression) => out.push(Node::Expression(expression)),
+            ArrayElement::Initializer(initializer) => array_initializer_children(initializer, out),
+        }
+    }
+}
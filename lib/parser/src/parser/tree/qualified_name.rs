@@ -1,3 +1,4 @@
+use crate::lexer::source::Source;
 use crate::lexer::span::{Span, Spanned};
 use crate::Identifier;
 
@@ -38,10 +39,28 @@ impl QualifiedName {
         Self { segments: vec![] }
     }
 
+    /// Creates a qualified name from the given segments, which are not part of
+    /// any source. The segments carry their text, see [`Span::synthetic`].
+    #[cfg(test)]
+    pub fn synthetic(segments: &[&str]) -> Self {
+        Self::from(segments.iter().map(|s| Span::synthetic(s)))
+    }
+
     pub fn segments(&self) -> &[Identifier] {
         &self.segments
     }
 
+    /// Resolves the segments in the given source and joins them with dots,
+    /// ignoring any whitespace or comments between the segments.
+    pub fn resolve(&self, source: &Source) -> Option<String> {
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| source.resolve_span(*segment.span()))
+            .collect::<Option<Vec<_>>>()?;
+        Some(segments.join("."))
+    }
+
     pub(in crate::parser) fn push(&mut self, segment: Identifier) {
         self.segments.push(segment);
     }
@@ -7,10 +7,27 @@ use crate::{
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Statement {
-    label: Identifier,
+    label: Option<Identifier>,
     statement: StatementKind,
 }
 
+impl Statement {
+    pub(in crate::parser) fn new(statement: StatementKind) -> Self {
+        Self {
+            label: None,
+            statement,
+        }
+    }
+
+    pub fn label(&self) -> Option<&Identifier> {
+        self.label.as_ref()
+    }
+
+    pub fn kind(&self) -> &StatementKind {
+        &self.statement
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum StatementKind {
     Empty,
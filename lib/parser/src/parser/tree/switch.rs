@@ -1,15 +1,75 @@
 use crate::parser::tree::statement::Statement;
-use crate::Expression;
+use crate::{Expression, Identifier, Type};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SwitchStatement {
     selector: Expression,
     cases: Vec<SwitchCase>,
-    default: Option<Vec<Statement>>, // not technically a block
+}
+
+impl SwitchStatement {
+    pub(in crate::parser) fn new(selector: Expression) -> Self {
+        Self {
+            selector,
+            cases: vec![],
+        }
+    }
+
+    pub(in crate::parser) fn add_case(&mut self, case: SwitchCase) {
+        self.cases.push(case);
+    }
+
+    pub fn selector(&self) -> &Expression {
+        &self.selector
+    }
+
+    pub fn cases(&self) -> &[SwitchCase] {
+        &self.cases
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SwitchCase {
-    label: Option<Expression>,
+    labels: Vec<CaseLabel>,
+    arrow: bool,
     statements: Vec<Statement>, // not technically a block
 }
+
+impl SwitchCase {
+    pub(in crate::parser) fn new(labels: Vec<CaseLabel>, arrow: bool) -> Self {
+        Self {
+            labels,
+            arrow,
+            statements: vec![],
+        }
+    }
+
+    pub(in crate::parser) fn add_statement(&mut self, statement: Statement) {
+        self.statements.push(statement);
+    }
+
+    /// The labels of this case. `case null, default` has two labels, and a
+    /// plain `default` has only the [`CaseLabel::Default`] label.
+    pub fn labels(&self) -> &[CaseLabel] {
+        &self.labels
+    }
+
+    /// Whether this case is written as `case x ->`, which doesn't fall through,
+    /// as opposed to `case x:`.
+    pub fn is_arrow(&self) -> bool {
+        self.arrow
+    }
+
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CaseLabel {
+    Null,
+    Default,
+    Expression(Expression),
+    /// A type pattern like `String s` in `case String s ->`.
+    TypePattern(Type, Identifier),
+}
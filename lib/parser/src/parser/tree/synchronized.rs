@@ -5,3 +5,18 @@ pub struct SynchronizedStatement {
     expression: Expression,
     block: Block,
 }
+
+impl SynchronizedStatement {
+    pub(in crate::parser) fn new(expression: Expression, block: Block) -> Self {
+        Self { expression, block }
+    }
+
+    /// The expression whose value is locked, like `lock` in `synchronized (lock) {}`.
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+}
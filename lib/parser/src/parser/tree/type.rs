@@ -0,0 +1,194 @@
+use crate::lexer::span::{Span, Spanned};
+use crate::lexer::GraphemeIndex;
+use crate::{Identifier, QualifiedName};
+
+/// A type like `String`, `Map<K, V>` or `int[]`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Type {
+    kind: TypeKind,
+    type_arguments: Vec<TypeArgument>,
+    dimensions: usize,
+    span: Span,
+}
+
+/// A type without its type arguments and array dimensions.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TypeKind {
+    Primitive(PrimitiveKind),
+    /// A class, interface or type variable, like `String` or `T`.
+    Reference(QualifiedName),
+}
+
+/// The kind of a primitive type, like `int`.
+///
+/// There is no kind for `void`, as `void` isn't a type but marks a method
+/// without a result, which can't be used for fields, variables or type
+/// arguments. A `void` method has no return type instead, see
+/// [`MethodDeclaration::return_type`](crate::MethodDeclaration::return_type).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PrimitiveKind {
+    Boolean,
+    Byte,
+    Short,
+    Int,
+    Long,
+    Char,
+    Float,
+    Double,
+}
+
+impl Type {
+    /// Creates a reference type, like `String` or `List<T>`.
+    pub(in crate::parser) fn new(
+        name: QualifiedName,
+        type_arguments: Vec<TypeArgument>,
+        span: Span,
+    ) -> Self {
+        Self {
+            kind: TypeKind::Reference(name),
+            type_arguments,
+            dimensions: 0,
+            span,
+        }
+    }
+
+    /// Creates a primitive type from the span of its keyword.
+    pub(in crate::parser) fn primitive(kind: PrimitiveKind, span: Span) -> Self {
+        Self {
+            kind: TypeKind::Primitive(kind),
+            type_arguments: vec![],
+            dimensions: 0,
+            span,
+        }
+    }
+
+    /// Adds an array dimension to this type, extending its span to the
+    /// given end, which is the end of the closing `]`.
+    pub(in crate::parser) fn add_dimension(&mut self, end: GraphemeIndex) {
+        self.dimensions += 1;
+        self.span = Span::new(self.span.start(), end);
+    }
+
+    pub fn kind(&self) -> &TypeKind {
+        &self.kind
+    }
+
+    /// The name of a reference type, or `None` for a primitive type.
+    pub fn name(&self) -> Option<&QualifiedName> {
+        match &self.kind {
+            TypeKind::Primitive(_) => None,
+            TypeKind::Reference(name) => Some(name),
+        }
+    }
+
+    /// The kind of a primitive type, or `None` for a reference type.
+    pub fn primitive_kind(&self) -> Option<PrimitiveKind> {
+        match self.kind {
+            TypeKind::Primitive(kind) => Some(kind),
+            TypeKind::Reference(_) => None,
+        }
+    }
+
+    pub fn type_arguments(&self) -> &[TypeArgument] {
+        &self.type_arguments
+    }
+
+    /// The number of array dimensions of this type, like 2 for `int[][]`.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.dimensions > 0
+    }
+}
+
+impl From<QualifiedName> for Type {
+    /// Creates a reference type without type arguments. The span of the type
+    /// is the span of the name.
+    ///
+    /// # Panics
+    /// Panics if the name has no segments.
+    fn from(name: QualifiedName) -> Self {
+        let span = name.span().expect("name of a type must not be empty");
+        Self::new(name, vec![], span)
+    }
+}
+
+impl Spanned for Type {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+/// A type argument of a generic type, like `String` or `? extends Number`
+/// in `Map<String, ? extends Number>`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TypeArgument {
+    Type(Type),
+    Wildcard(Wildcard),
+}
+
+impl Spanned for TypeArgument {
+    fn span(&self) -> Option<Span> {
+        match self {
+            TypeArgument::Type(ty) => ty.span(),
+            TypeArgument::Wildcard(wildcard) => wildcard.span(),
+        }
+    }
+}
+
+/// A wildcard type argument like `?`, `? extends Number` or `? super Integer`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Wildcard {
+    bound: Option<WildcardBound>,
+    span: Span,
+}
+
+impl Wildcard {
+    pub(in crate::parser) fn new(bound: Option<WildcardBound>, span: Span) -> Self {
+        Self { bound, span }
+    }
+
+    pub fn bound(&self) -> Option<&WildcardBound> {
+        self.bound.as_ref()
+    }
+}
+
+impl Spanned for Wildcard {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum WildcardBound {
+    /// An upper bound, like `Number` in `? extends Number`.
+    Extends(Type),
+    /// A lower bound, like `Integer` in `? super Integer`.
+    Super(Type),
+}
+
+/// A type parameter like `T` or `T extends Comparable<T> & Serializable`
+/// in a generic declaration.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TypeParameter {
+    name: Identifier,
+    bounds: Vec<Type>,
+}
+
+impl TypeParameter {
+    pub(in crate::parser) fn new(name: Identifier, bounds: Vec<Type>) -> Self {
+        Self { name, bounds }
+    }
+
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// The bounds of this type parameter, which are all types after `extends`,
+    /// separated by `&`.
+    pub fn bounds(&self) -> &[Type] {
+        &self.bounds
+    }
+}
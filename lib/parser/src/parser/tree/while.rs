@@ -5,3 +5,13 @@ pub struct WhileStatement {
     condition: Expression,
     block: Block,
 }
+
+impl WhileStatement {
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+}